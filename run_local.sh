@@ -2,7 +2,7 @@
 
 # Parse command line arguments
 SELECTED_BENCHMARKS="$1"
-AVAILABLE_BENCHMARKS=("web" "rust" "profiled" "wasi" "nativecpu")
+AVAILABLE_BENCHMARKS=("web" "rust" "rustisolated" "profiled" "wasi" "wasirunner" "wasithreads" "nativecpu")
 
 function printable_benchmarks() {
   echo "$(IFS=', '; echo "${AVAILABLE_BENCHMARKS[*]}")"
@@ -85,6 +85,17 @@ else
   echo "Skipping rust benchmarks."
 fi
 
+# Perform rust native benchmarks, every one of them in 5 fresh processes
+if should_run_benchmark "rustisolated"; then
+  echo "Running rust isolated benchmark..."
+  cd ../rust_benchs
+  ISOLATE=5 cargo run --release -- nativeisolated
+  mv benchmark_*.json ../results/
+  cd ../results
+else
+  echo "Skipping rust isolated benchmarks."
+fi
+
 if should_run_benchmark "profiled"; then
   echo "Running rust profiled benchmark..."
 
@@ -126,3 +137,30 @@ else
   echo "Skipping rust benchmarks."
 fi
 
+# Perform rust wasi benchmarks in the embedded wasmtime of wasm-runner, with the
+# engine settings in wasm_runner.json
+if should_run_benchmark "wasirunner"; then
+  rustup target add wasm32-wasip2 --toolchain nightly
+  echo "Running rust wasi benchmark in wasm-runner..."
+  cd ../rust_benchs
+  cargo build --release --target wasm32-wasip2
+  cargo run --release --features wasm-runner --bin wasm-runner -- wasm_runner.json
+  mv benchmark_*.json ../results/
+  cd ../results
+else
+  echo "Skipping rust wasi runner benchmarks."
+fi
+
+# Perform rust wasi benchmarks with threads, so the sync kernels can spawn them
+if should_run_benchmark "wasithreads"; then
+  rustup target add wasm32-wasip1-threads --toolchain nightly
+  echo "Running rust wasi threads benchmark..."
+  cd ../rust_benchs
+  cargo build --release --target wasm32-wasip1-threads
+  TWO_POW_32=$((2**32))
+  wasmtime -C compiler=cranelift -W threads=y -S threads=y -O signals-based-traps=y,memory-reservation=$TWO_POW_32,memory-guard-size=$TWO_POW_32 --dir=.. --dir=. target/wasm32-wasip1-threads/release/rust_benchs.wasm wasmtimethreads
+  mv benchmark_*.json ../results/
+  cd ../results
+else
+  echo "Skipping rust wasi threads benchmarks."
+fi
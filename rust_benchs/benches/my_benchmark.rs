@@ -6,7 +6,7 @@ use std::{fs::File, time::Instant};
 
 use rust_benchs::{eratostenes, fibonacci, quicksort};
 use std::io::Write;
-use test::{Bencher, black_box};
+use test::Bencher;
 
 fn perform_benchmark<F: Fn() -> T, T>(b: &mut Bencher, f: F, name: &str) {
     println!("Benchmarking {}...", name);
@@ -76,7 +76,7 @@ fn bench_all(b: &mut Bencher) {
                 };
                 let arg: Vec<i32> = serde_json::from_value(test["arg"].clone()).unwrap();
                 let name = test["name"].as_str().unwrap();
-                perform_benchmark_2(b, fun, name, &arg);
+                perform_benchmark_2(b, |v: &mut Vec<i32>| fun(v), name, arg);
             }
             _ => continue,
         }
@@ -0,0 +1,163 @@
+use std::hint::black_box;
+use std::slice;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+// Each pair of kernels below does the same work in two styles and must return
+// the same checksum, so any difference in time is the cost of the style.
+
+/// Generates `len` random values for the abstraction kernels.
+#[wasm_bindgen]
+pub fn abstraction_data(len: usize, seed: u32) -> Vec<u32> {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    (0..len).map(|_| rng.random()).collect()
+}
+
+// Order-dependent hash step shared by every kernel
+fn combine(hash: u32, value: u32) -> u32 {
+    hash.rotate_left(5) ^ value
+}
+
+/// Sums the products of every increasing pair of neighbours, with `windows`,
+/// `filter` and `map`.
+#[wasm_bindgen]
+pub fn abstraction_iterator(data: &[u32]) -> u32 {
+    data.windows(2)
+        .filter(|pair| pair[0] < pair[1])
+        .map(|pair| pair[0].wrapping_mul(pair[1]))
+        .fold(0, combine)
+}
+
+/// Same as `abstraction_iterator` with an index loop, which has to check the
+/// bounds of every access.
+#[wasm_bindgen]
+pub fn abstraction_index(data: &[u32]) -> u32 {
+    let mut hash = 0;
+    for i in 1..data.len() {
+        if data[i - 1] < data[i] {
+            hash = combine(hash, data[i - 1].wrapping_mul(data[i]));
+        }
+    }
+    hash
+}
+
+trait Mix {
+    fn mix(&self, value: u32) -> u32;
+}
+
+struct AddMix(u32);
+struct MulMix(u32);
+struct RotateMix(u32);
+
+impl Mix for AddMix {
+    fn mix(&self, value: u32) -> u32 {
+        value.wrapping_add(self.0)
+    }
+}
+
+impl Mix for MulMix {
+    fn mix(&self, value: u32) -> u32 {
+        value.wrapping_mul(self.0)
+    }
+}
+
+impl Mix for RotateMix {
+    fn mix(&self, value: u32) -> u32 {
+        value.rotate_left(self.0) ^ value
+    }
+}
+
+const ADD_MIX: AddMix = AddMix(0x9e3779b9);
+const MUL_MIX: MulMix = MulMix(0x85ebca6b);
+const ROTATE_MIX: RotateMix = RotateMix(13);
+
+fn mix_all<M: Mix>(data: &[u32], mix: &M) -> u32 {
+    data.iter()
+        .fold(0, |hash, &value| combine(hash, mix.mix(value)))
+}
+
+fn mix_all_dyn(data: &[u32], mix: &dyn Mix) -> u32 {
+    data.iter()
+        .fold(0, |hash, &value| combine(hash, mix.mix(value)))
+}
+
+/// Hashes the data once per `Mix` implementation, monomorphised for each.
+#[wasm_bindgen]
+pub fn abstraction_generic(data: &[u32]) -> u32 {
+    mix_all(data, &ADD_MIX)
+        .wrapping_add(mix_all(data, &MUL_MIX))
+        .wrapping_add(mix_all(data, &ROTATE_MIX))
+}
+
+/// Same as `abstraction_generic` through `&dyn Mix`. The trait objects go
+/// through `black_box` so the compiler can't devirtualize the calls, as if they
+/// came from elsewhere in the program.
+#[wasm_bindgen]
+pub fn abstraction_dyn(data: &[u32]) -> u32 {
+    let mixes: [&dyn Mix; 3] = black_box([&ADD_MIX, &MUL_MIX, &ROTATE_MIX]);
+    mixes
+        .iter()
+        .fold(0, |sum, &mix| sum.wrapping_add(mix_all_dyn(data, mix)))
+}
+
+const SCRAMBLE_MULTIPLIER: u32 = 0x2c1b3c6d;
+const SCRAMBLE_SHIFT: u32 = 15;
+
+fn scramble(value: u32) -> u32 {
+    value.wrapping_mul(SCRAMBLE_MULTIPLIER) ^ (value >> SCRAMBLE_SHIFT)
+}
+
+fn scramble_all(data: &[u32], f: impl Fn(u32) -> u32) -> u32 {
+    data.iter().fold(0, |hash, &value| combine(hash, f(value)))
+}
+
+fn scramble_all_fn_pointer(data: &[u32], f: fn(u32) -> u32) -> u32 {
+    data.iter().fold(0, |hash, &value| combine(hash, f(value)))
+}
+
+/// Hashes the data through a closure passed as `impl Fn`, which is inlined.
+#[wasm_bindgen]
+pub fn abstraction_closure(data: &[u32]) -> u32 {
+    scramble_all(data, |value| {
+        value.wrapping_mul(SCRAMBLE_MULTIPLIER) ^ (value >> SCRAMBLE_SHIFT)
+    })
+}
+
+/// Same as `abstraction_closure` through a `fn` pointer, hidden behind
+/// `black_box` so every call stays indirect.
+#[wasm_bindgen]
+pub fn abstraction_fn_pointer(data: &[u32]) -> u32 {
+    scramble_all_fn_pointer(data, black_box(scramble as fn(u32) -> u32))
+}
+
+#[wasm_bindgen]
+pub fn abstraction_iterator_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_iterator(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn abstraction_index_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_index(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn abstraction_generic_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_generic(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn abstraction_dyn_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_dyn(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn abstraction_closure_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_closure(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn abstraction_fn_pointer_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    abstraction_fn_pointer(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
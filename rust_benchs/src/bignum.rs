@@ -0,0 +1,317 @@
+use wasm_bindgen::prelude::*;
+
+// Largest power of ten that fits in a limb, used to print 19 digits at a time
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+
+/// Arbitrary precision unsigned integer, stored as little-endian 64-bit limbs.
+/// Every limb product goes through `u128`, which wasm has to emulate, and the
+/// kernels spend their time in those products. Dividing by a limb goes through
+/// `u128` too, but is only used to print the results and to start the
+/// reciprocal of pi's denominator.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+#[wasm_bindgen]
+impl BigUint {
+    pub fn limbs(&self) -> u32 {
+        self.limbs.len() as u32
+    }
+
+    /// Decimal representation, by repeated division. It is quadratic in the
+    /// number of limbs, so the kernels leave it to the caller.
+    pub fn to_decimal(&self) -> String {
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        loop {
+            chunks.push(value.div_small(DECIMAL_CHUNK));
+            value.trim();
+            if value.is_zero() {
+                break;
+            }
+        }
+
+        let mut decimal = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            decimal.push_str(&format!("{:019}", chunk));
+        }
+        decimal
+    }
+
+    /// For `bignum_pi_value`'s result, the first `digits` decimal digits after
+    /// the point, preceded by "3.".
+    pub fn pi_decimal(&self, digits: u32) -> String {
+        // The top limb holds the integer part
+        let fraction_limbs = self.limbs.len() - 1;
+        let mut decimal = format!("{}.", self.limbs[fraction_limbs]);
+        let mut fraction = BigUint {
+            limbs: self.limbs[..fraction_limbs].to_vec(),
+        };
+        while decimal.len() < digits as usize + 2 {
+            // The limb pushed by the multiplication holds the next digits
+            fraction.mul_small(DECIMAL_CHUNK);
+            let chunk = if fraction.limbs.len() > fraction_limbs {
+                fraction.limbs.pop().unwrap()
+            } else {
+                0
+            };
+            decimal.push_str(&format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS));
+        }
+        decimal.truncate(digits as usize + 2);
+        decimal
+    }
+}
+
+impl BigUint {
+    fn from_u64(value: u64) -> BigUint {
+        BigUint { limbs: vec![value] }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&limb| limb == 0)
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.len() > 1 && *self.limbs.last().unwrap() == 0 {
+            self.limbs.pop();
+        }
+    }
+
+    fn mul_small(&mut self, factor: u64) {
+        let mut carry: u64 = 0;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u128 * factor as u128 + carry as u128;
+            *limb = product as u64;
+            carry = (product >> 64) as u64;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    /// Divides in place, returning the remainder.
+    fn div_small(&mut self, divisor: u64) -> u64 {
+        let mut remainder: u64 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let dividend = ((remainder as u128) << 64) | *limb as u128;
+            *limb = (dividend / divisor as u128) as u64;
+            remainder = (dividend % divisor as u128) as u64;
+        }
+        remainder
+    }
+
+    // 2^(64 n), i.e. 1 followed by `n` zero limbs
+    fn limb_power(n: usize) -> BigUint {
+        let mut limbs = vec![0; n + 1];
+        limbs[n] = 1;
+        BigUint { limbs }
+    }
+
+    // Drops the `n` lowest limbs, i.e. divides by 2^(64 n)
+    fn shr_limbs(&self, n: usize) -> BigUint {
+        match self.limbs.get(n..) {
+            Some(limbs) if !limbs.is_empty() => BigUint {
+                limbs: limbs.to_vec(),
+            },
+            _ => BigUint::from_u64(0),
+        }
+    }
+
+    fn add(&self, other: &BigUint) -> BigUint {
+        let mut sum = self.clone();
+        sum.limbs
+            .resize(self.limbs.len().max(other.limbs.len()) + 1, 0);
+        let mut addend = other.clone();
+        addend.limbs.resize(sum.limbs.len(), 0);
+        sum.add_assign(&addend);
+        sum.trim();
+        sum
+    }
+
+    // Needs self >= other
+    fn sub(&self, other: &BigUint) -> BigUint {
+        let mut difference = self.clone();
+        let mut subtrahend = other.clone();
+        subtrahend.limbs.resize(difference.limbs.len(), 0);
+        difference.sub_assign(&subtrahend);
+        difference.trim();
+        difference
+    }
+
+    fn mul(&self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u128 * b as u128 + limbs[i + j] as u128 + carry as u128;
+                limbs[i + j] = product as u64;
+                carry = (product >> 64) as u64;
+            }
+            limbs[i + other.limbs.len()] = carry;
+        }
+        let mut result = BigUint { limbs };
+        result.trim();
+        result
+    }
+
+    /// About 2^(128 m) / self for an m-limb number, a few units short at
+    /// most, by Newton's iteration r += r (2^(128 m) - self r) / 2^(128 m).
+    /// It starts below the quotient, so r stays below it and all of the
+    /// values are positive.
+    fn reciprocal(&self) -> BigUint {
+        let m = self.limbs.len();
+        let one = BigUint::limb_power(2 * m);
+        // self < (top + 1) 2^(64 (m - 1)), so this start is below the
+        // quotient and at least half of it
+        let mut r = match self.limbs[m - 1].checked_add(1) {
+            Some(top) => {
+                let mut r = BigUint::limb_power(m + 1);
+                r.div_small(top);
+                r.trim();
+                r
+            }
+            None => BigUint::limb_power(m),
+        };
+        loop {
+            let error = one.sub(&self.mul(&r));
+            let correction = r.mul(&error).shr_limbs(2 * m);
+            if correction.is_zero() {
+                return r;
+            }
+            r = r.add(&correction);
+        }
+    }
+
+    // Fixed-point helpers: both operands must have the same number of limbs
+    fn add_assign(&mut self, other: &BigUint) {
+        let mut carry = false;
+        for (a, &b) in self.limbs.iter_mut().zip(&other.limbs) {
+            let (sum, overflow_a) = a.overflowing_add(b);
+            let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+            *a = sum;
+            carry = overflow_a || overflow_b;
+        }
+    }
+
+    fn sub_assign(&mut self, other: &BigUint) {
+        let mut borrow = false;
+        for (a, &b) in self.limbs.iter_mut().zip(&other.limbs) {
+            let (difference, borrow_a) = a.overflowing_sub(b);
+            let (difference, borrow_b) = difference.overflowing_sub(borrow as u64);
+            *a = difference;
+            borrow = borrow_a || borrow_b;
+        }
+    }
+}
+
+/// `n!`.
+#[wasm_bindgen]
+pub fn bignum_factorial_value(n: u32) -> BigUint {
+    let mut result = BigUint::from_u64(1);
+    for factor in 2..=n as u64 {
+        result.mul_small(factor);
+    }
+    result
+}
+
+/// `base^exponent`, computed by repeated squaring with schoolbook
+/// multiplication.
+#[wasm_bindgen]
+pub fn bignum_power_value(base: u64, exponent: u32) -> BigUint {
+    let mut result = BigUint::from_u64(1);
+    let mut square = BigUint::from_u64(base);
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.mul(&square);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            square = square.mul(&square);
+        }
+    }
+    result
+}
+
+/// Decimal representation of `n!`.
+#[wasm_bindgen]
+pub fn bignum_factorial(n: u32) -> String {
+    bignum_factorial_value(n).to_decimal()
+}
+
+/// Decimal representation of `base^exponent`.
+#[wasm_bindgen]
+pub fn bignum_power(base: u64, exponent: u32) -> String {
+    bignum_power_value(base, exponent).to_decimal()
+}
+
+// Binary splitting of the terms a..b of Euler's series
+//   arctan(1/x) = x / (x² + 1) Σ_k Π_{j=1..k} 2j / ((2j + 1) (x² + 1))
+// whose terms, unlike Taylor's, are all positive. Returns P and Q, the
+// products of the numerators and the denominators of the ratios j = a..b,
+// and T with T / Q = Σ_{k=a..b} Π_{j=a..k} of the ratios.
+fn arctan_split(x_squared_plus_one: u64, a: u64, b: u64) -> (BigUint, BigUint, BigUint) {
+    if b - a == 1 {
+        let p = BigUint::from_u64(2 * a);
+        let q = BigUint::from_u64((2 * a + 1) * x_squared_plus_one);
+        return (p.clone(), q, p);
+    }
+    let middle = (a + b) / 2;
+    let (p_left, q_left, t_left) = arctan_split(x_squared_plus_one, a, middle);
+    let (p_right, q_right, t_right) = arctan_split(x_squared_plus_one, middle, b);
+    let t = t_left.mul(&q_right).add(&p_left.mul(&t_right));
+    (p_left.mul(&p_right), q_left.mul(&q_right), t)
+}
+
+// arctan(1 / x) as a fraction, with enough terms of the series for `bits`
+// bits after the point
+fn arctan_inverse(x: u64, bits: usize) -> (BigUint, BigUint) {
+    let x_squared_plus_one = x * x + 1;
+    // Every ratio is below 1 / (x² + 1)
+    let terms = bits.div_ceil(x_squared_plus_one.ilog2() as usize) as u64 + 1;
+    let (_, q, t) = arctan_split(x_squared_plus_one, 1, terms);
+    // x (1 + T / Q) / (x² + 1)
+    let mut numerator = q.add(&t);
+    numerator.mul_small(x);
+    let mut denominator = q;
+    denominator.mul_small(x_squared_plus_one);
+    (numerator, denominator)
+}
+
+/// Pi in fixed point with enough limbs after the point for `digits` decimal
+/// digits, using Machin's formula pi = 16 arctan(1/5) - 4 arctan(1/239). The
+/// arctangents are fractions from binary splitting, and the one division at
+/// the end multiplies by a reciprocal, so the time goes into `mul`.
+#[wasm_bindgen]
+pub fn bignum_pi_value(digits: u32) -> BigUint {
+    // log2(10) < 3.33 bits per digit, plus two guard limbs for rounding errors
+    let fraction_limbs = (digits as usize * 333 / 100).div_ceil(64) + 2;
+
+    let (mut numerator_5, denominator_5) = arctan_inverse(5, 64 * fraction_limbs);
+    let (mut numerator_239, denominator_239) = arctan_inverse(239, 64 * fraction_limbs);
+    numerator_5.mul_small(16);
+    numerator_239.mul_small(4);
+    let numerator = numerator_5
+        .mul(&denominator_239)
+        .sub(&numerator_239.mul(&denominator_5));
+    let denominator = denominator_5.mul(&denominator_239);
+
+    // Only the top limbs of the fraction matter for the precision asked for.
+    // The denominator has more limbs than that, as each of its factors is
+    // above 2^(64 fraction_limbs)
+    let m = fraction_limbs + 2;
+    let dropped = denominator.limbs.len() - m;
+    let numerator = numerator.shr_limbs(dropped);
+    let reciprocal = denominator.shr_limbs(dropped).reciprocal();
+    // numerator 2^(64 fraction_limbs) / denominator
+    numerator.mul(&reciprocal).shr_limbs(2 * m - fraction_limbs)
+}
+
+/// The first `digits` decimal digits of pi after the point, preceded by "3.".
+#[wasm_bindgen]
+pub fn bignum_pi(digits: u32) -> String {
+    bignum_pi_value(digits).pi_decimal(digits)
+}
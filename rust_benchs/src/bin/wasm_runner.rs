@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer};
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Config, Engine, OptLevel, Store, Strategy};
+use wasmtime_wasi::p2::bindings::sync::Command;
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtx, WasiCtxView, WasiView};
+
+// Runs the `wasm32-wasip2` build of the benchmarks in an embedded wasmtime,
+// instead of the `wasmtime` CLI, so every engine setting is spelled out in a
+// config file rather than in `-C`/`-O` flags. The config is a JSON file with
+// any of the fields of `RunnerConfig`, given as the only argument; the ones
+// left out default to the settings `run_local.sh` always used.
+
+const DEFAULT_WASM: &str = "target/wasm32-wasip2/release/rust_benchs.wasm";
+const FOUR_GIB: u64 = 1 << 32;
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Compiler {
+    Cranelift,
+    Winch,
+}
+
+impl Compiler {
+    fn name(&self) -> &'static str {
+        match self {
+            Compiler::Cranelift => "cranelift",
+            Compiler::Winch => "winch",
+        }
+    }
+}
+
+/// Cranelift's optimization level. Winch doesn't optimize and ignores it.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OptimizationLevel {
+    None,
+    Speed,
+    SpeedAndSize,
+}
+
+impl OptimizationLevel {
+    fn name(&self) -> &'static str {
+        match self {
+            OptimizationLevel::None => "none",
+            OptimizationLevel::Speed => "speed",
+            OptimizationLevel::SpeedAndSize => "speedandsize",
+        }
+    }
+}
+
+/// Settings of the engine, and what to run with it.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RunnerConfig {
+    compiler: Compiler,
+    opt_level: OptimizationLevel,
+    /// Catch out-of-bounds accesses with the guard pages and a signal handler
+    /// instead of explicit bounds checks.
+    signals_based_traps: bool,
+    /// Virtual memory reserved for every linear memory, in bytes.
+    memory_reservation: u64,
+    /// Unmapped memory after every linear memory, in bytes.
+    memory_guard_size: u64,
+    /// The component to run.
+    wasm: PathBuf,
+    /// Runtime name the results are written under. By default it is made of
+    /// the settings above, see `RunnerConfig::label`.
+    #[serde(deserialize_with = "runtime_label")]
+    label: Option<String>,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> RunnerConfig {
+        RunnerConfig {
+            compiler: Compiler::Cranelift,
+            opt_level: OptimizationLevel::Speed,
+            signals_based_traps: true,
+            memory_reservation: FOUR_GIB,
+            memory_guard_size: FOUR_GIB,
+            wasm: PathBuf::from(DEFAULT_WASM),
+            label: None,
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Name of the runtime in the results files, e.g.
+    /// `wasmtime-cranelift-speed-signals-r4G-g4G` for the defaults. It can't
+    /// have underscores, which separate the runtime from the benchmark name.
+    fn label(&self) -> String {
+        if let Some(label) = &self.label {
+            return label.clone();
+        }
+        format!(
+            "wasmtime-{}-{}-{}-r{}-g{}",
+            self.compiler.name(),
+            self.opt_level.name(),
+            if self.signals_based_traps {
+                "signals"
+            } else {
+                "checks"
+            },
+            byte_size(self.memory_reservation),
+            byte_size(self.memory_guard_size)
+        )
+    }
+
+    fn engine(&self) -> wasmtime::Result<Engine> {
+        let mut config = Config::new();
+        config
+            .strategy(match self.compiler {
+                Compiler::Cranelift => Strategy::Cranelift,
+                Compiler::Winch => Strategy::Winch,
+            })
+            .cranelift_opt_level(match self.opt_level {
+                OptimizationLevel::None => OptLevel::None,
+                OptimizationLevel::Speed => OptLevel::Speed,
+                OptimizationLevel::SpeedAndSize => OptLevel::SpeedAndSize,
+            })
+            .signals_based_traps(self.signals_based_traps)
+            .memory_reservation(self.memory_reservation)
+            .memory_guard_size(self.memory_guard_size);
+        Engine::new(&config)
+    }
+}
+
+// A label given in the config, which has to be usable as the runtime part of
+// the results filenames
+fn runtime_label<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let label = String::deserialize(deserializer)?;
+    if label.is_empty() || label.contains(['_', '/', '\\']) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid label {label:?}: it must be non-empty, without underscores or path separators"
+        )));
+    }
+    Ok(Some(label))
+}
+
+// A size in the largest binary unit that divides it, e.g. 4G or 64K
+fn byte_size(bytes: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1 << 30, "G"), (1 << 20, "M"), (1 << 10, "K")];
+    UNITS
+        .iter()
+        .find(|&&(unit, _)| bytes != 0 && bytes.is_multiple_of(unit))
+        .map_or_else(
+            || bytes.to_string(),
+            |&(unit, suffix)| format!("{}{}", bytes / unit, suffix),
+        )
+}
+
+struct State {
+    ctx: WasiCtx,
+    table: ResourceTable,
+}
+
+impl WasiView for State {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.ctx,
+            table: &mut self.table,
+        }
+    }
+}
+
+pub fn main() -> wasmtime::Result<()> {
+    let config = match std::env::args().nth(1) {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => RunnerConfig::default(),
+    };
+    let label = config.label();
+    eprintln!("Running {} as {}...", config.wasm.display(), label);
+
+    let engine = config.engine()?;
+    let component = Component::from_file(&engine, &config.wasm)?;
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
+
+    // The same environment the CLI gave it: the inputs are read from `..` and
+    // the results written to `.`
+    let ctx = WasiCtx::builder()
+        .inherit_stdio()
+        .inherit_env()
+        .args(&["rust_benchs", &label])
+        .preopened_dir("..", "..", DirPerms::all(), FilePerms::all())?
+        .preopened_dir(".", ".", DirPerms::all(), FilePerms::all())?
+        .build();
+    let mut store = Store::new(
+        &engine,
+        State {
+            ctx,
+            table: ResourceTable::new(),
+        },
+    );
+
+    let command = Command::instantiate(&mut store, &component, &linker)?;
+    if command.wasi_cli_run().call_run(&mut store)?.is_err() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use std::slice;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+/// Generates a random sequence of `len` letters taken from the first
+/// `alphabet` uppercase letters (4 gives DNA-like strings).
+#[wasm_bindgen]
+pub fn dp_sequence(len: usize, alphabet: u8, seed: u32) -> Vec<u8> {
+    assert!(
+        (1..=26).contains(&alphabet),
+        "alphabet must have between 1 and 26 letters, got {}",
+        alphabet
+    );
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    (0..len)
+        .map(|_| b'A' + rng.random_range(0..alphabet))
+        .collect()
+}
+
+// Both table variants store the (a.len() + 1) x (b.len() + 1) table row by
+// row: index = i * (b.len() + 1) + j
+
+#[wasm_bindgen]
+pub fn levenshtein_full(a: &[u8], b: &[u8]) -> u32 {
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+
+    for (j, cell) in table[..width].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+    for i in 1..=a.len() {
+        table[i * width] = i as u32;
+        for j in 1..width {
+            let substitution = table[(i - 1) * width + j - 1] + (a[i - 1] != b[j - 1]) as u32;
+            let deletion = table[(i - 1) * width + j] + 1;
+            let insertion = table[i * width + j - 1] + 1;
+            table[i * width + j] = substitution.min(deletion).min(insertion);
+        }
+    }
+
+    table[a.len() * width + b.len()]
+}
+
+/// Same as `levenshtein_full`, keeping only the previous and current rows.
+#[wasm_bindgen]
+pub fn levenshtein_two_rows(a: &[u8], b: &[u8]) -> u32 {
+    let mut previous: Vec<u32> = (0..=b.len() as u32).collect();
+    let mut current = vec![0u32; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i as u32;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + (a[i - 1] != b[j - 1]) as u32;
+            let deletion = previous[j] + 1;
+            let insertion = current[j - 1] + 1;
+            current[j] = substitution.min(deletion).min(insertion);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Length of the longest common subsequence of `a` and `b`.
+#[wasm_bindgen]
+pub fn lcs_full(a: &[u8], b: &[u8]) -> u32 {
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+
+    for i in 1..=a.len() {
+        for j in 1..width {
+            table[i * width + j] = if a[i - 1] == b[j - 1] {
+                table[(i - 1) * width + j - 1] + 1
+            } else {
+                table[(i - 1) * width + j].max(table[i * width + j - 1])
+            };
+        }
+    }
+
+    table[a.len() * width + b.len()]
+}
+
+/// Same as `lcs_full`, keeping only the previous and current rows.
+#[wasm_bindgen]
+pub fn lcs_two_rows(a: &[u8], b: &[u8]) -> u32 {
+    let mut previous = vec![0u32; b.len() + 1];
+    let mut current = vec![0u32; b.len() + 1];
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            current[j] = if a[i - 1] == b[j - 1] {
+                previous[j - 1] + 1
+            } else {
+                previous[j].max(current[j - 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[wasm_bindgen]
+pub fn levenshtein_full_in_memory(
+    a_ptr: *const u8,
+    a_len: usize,
+    b_ptr: *const u8,
+    b_len: usize,
+) -> u32 {
+    let a = unsafe { slice::from_raw_parts(a_ptr, a_len) };
+    let b = unsafe { slice::from_raw_parts(b_ptr, b_len) };
+    levenshtein_full(a, b)
+}
+
+#[wasm_bindgen]
+pub fn levenshtein_two_rows_in_memory(
+    a_ptr: *const u8,
+    a_len: usize,
+    b_ptr: *const u8,
+    b_len: usize,
+) -> u32 {
+    let a = unsafe { slice::from_raw_parts(a_ptr, a_len) };
+    let b = unsafe { slice::from_raw_parts(b_ptr, b_len) };
+    levenshtein_two_rows(a, b)
+}
+
+#[wasm_bindgen]
+pub fn lcs_full_in_memory(a_ptr: *const u8, a_len: usize, b_ptr: *const u8, b_len: usize) -> u32 {
+    let a = unsafe { slice::from_raw_parts(a_ptr, a_len) };
+    let b = unsafe { slice::from_raw_parts(b_ptr, b_len) };
+    lcs_full(a, b)
+}
+
+#[wasm_bindgen]
+pub fn lcs_two_rows_in_memory(
+    a_ptr: *const u8,
+    a_len: usize,
+    b_ptr: *const u8,
+    b_len: usize,
+) -> u32 {
+    let a = unsafe { slice::from_raw_parts(a_ptr, a_len) };
+    let b = unsafe { slice::from_raw_parts(b_ptr, b_len) };
+    lcs_two_rows(a, b)
+}
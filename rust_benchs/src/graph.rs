@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+const MAX_WEIGHT: u32 = 100;
+const UNREACHED: u32 = u32::MAX;
+
+/// Weighted directed graph in compressed sparse row form: the edges leaving
+/// vertex `v` are `targets[offsets[v]..offsets[v + 1]]`, with their weights at
+/// the same positions in `weights`.
+#[wasm_bindgen]
+pub struct CsrGraph {
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    weights: Vec<u32>,
+}
+
+impl CsrGraph {
+    /// Graph with `vertices` vertices and the given `(from, to, weight)`
+    /// edges.
+    pub fn from_edges(vertices: u32, edges: &[(u32, u32, u32)]) -> CsrGraph {
+        let mut offsets = vec![0u32; vertices as usize + 1];
+        for &(from, _, _) in edges {
+            offsets[from as usize + 1] += 1;
+        }
+        for v in 0..vertices as usize {
+            offsets[v + 1] += offsets[v];
+        }
+
+        let mut next = offsets.clone();
+        let mut targets = vec![0u32; edges.len()];
+        let mut weights = vec![0u32; edges.len()];
+        for &(from, to, weight) in edges {
+            let slot = next[from as usize] as usize;
+            targets[slot] = to;
+            weights[slot] = weight;
+            next[from as usize] += 1;
+        }
+
+        CsrGraph {
+            offsets,
+            targets,
+            weights,
+        }
+    }
+
+    fn edges_of(&self, v: u32) -> std::ops::Range<usize> {
+        self.offsets[v as usize] as usize..self.offsets[v as usize + 1] as usize
+    }
+}
+
+#[wasm_bindgen]
+impl CsrGraph {
+    /// Graph with `edges` edges between uniformly chosen vertices.
+    pub fn random(vertices: u32, edges: u32, seed: u32) -> CsrGraph {
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let edges: Vec<_> = (0..edges)
+            .map(|_| {
+                (
+                    rng.random_range(0..vertices),
+                    rng.random_range(0..vertices),
+                    rng.random_range(1..=MAX_WEIGHT),
+                )
+            })
+            .collect();
+        CsrGraph::from_edges(vertices, &edges)
+    }
+
+    /// `width` x `height` grid where every cell links to its four neighbours.
+    /// Panics if the grid has more vertices or edges than fit in a `u32`.
+    pub fn grid(width: u32, height: u32, seed: u32) -> CsrGraph {
+        let vertices = width.checked_mul(height);
+        // Both ways between the h (w - 1) horizontal and w (h - 1) vertical
+        // pairs of neighbours
+        let edge_count = vertices.and_then(|vertices| {
+            let pairs = (2 * vertices as u64).saturating_sub(width as u64 + height as u64);
+            u32::try_from(2 * pairs).ok()
+        });
+        let (Some(vertices), Some(edge_count)) = (vertices, edge_count) else {
+            panic!("a {width}x{height} grid has too many vertices or edges");
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let mut edges = Vec::with_capacity(edge_count as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let v = y * width + x;
+                let mut link = |to: u32| edges.push((v, to, rng.random_range(1..=MAX_WEIGHT)));
+                if x > 0 {
+                    link(v - 1);
+                }
+                if x + 1 < width {
+                    link(v + 1);
+                }
+                if y > 0 {
+                    link(v - width);
+                }
+                if y + 1 < height {
+                    link(v + width);
+                }
+            }
+        }
+        CsrGraph::from_edges(vertices, &edges)
+    }
+
+    pub fn vertices(&self) -> u32 {
+        self.offsets.len() as u32 - 1
+    }
+
+    pub fn edges(&self) -> u32 {
+        self.targets.len() as u32
+    }
+}
+
+// Checksum of a distance array, covering both the distances and how many
+// vertices were reached
+fn distance_checksum(distances: &[u32]) -> u32 {
+    let mut sum: u32 = 0;
+    let mut reached: u32 = 0;
+    for &d in distances.iter().filter(|&&d| d != UNREACHED) {
+        sum = sum.wrapping_add(d);
+        reached += 1;
+    }
+    sum.wrapping_mul(31).wrapping_add(reached)
+}
+
+/// Unweighted shortest paths (in number of edges) from `source`.
+#[wasm_bindgen]
+pub fn graph_bfs(graph: &CsrGraph, source: u32) -> u32 {
+    let mut distances = vec![UNREACHED; graph.vertices() as usize];
+    let mut queue = VecDeque::new();
+
+    distances[source as usize] = 0;
+    queue.push_back(source);
+    while let Some(v) = queue.pop_front() {
+        let next = distances[v as usize] + 1;
+        for edge in graph.edges_of(v) {
+            let to = graph.targets[edge] as usize;
+            if distances[to] == UNREACHED {
+                distances[to] = next;
+                queue.push_back(to as u32);
+            }
+        }
+    }
+
+    distance_checksum(&distances)
+}
+
+/// Weighted shortest paths from `source`, using a `BinaryHeap` with lazy
+/// deletion of outdated entries.
+#[wasm_bindgen]
+pub fn graph_dijkstra(graph: &CsrGraph, source: u32) -> u32 {
+    let mut distances = vec![UNREACHED; graph.vertices() as usize];
+    let mut heap = BinaryHeap::new();
+
+    distances[source as usize] = 0;
+    heap.push(Reverse((0u32, source)));
+    while let Some(Reverse((distance, v))) = heap.pop() {
+        if distance > distances[v as usize] {
+            continue;
+        }
+        for edge in graph.edges_of(v) {
+            let to = graph.targets[edge] as usize;
+            let candidate = distance + graph.weights[edge];
+            if candidate < distances[to] {
+                distances[to] = candidate;
+                heap.push(Reverse((candidate, to as u32)));
+            }
+        }
+    }
+
+    distance_checksum(&distances)
+}
@@ -0,0 +1,567 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::heap::{HeapSnapshot, HeapStats};
+use crate::perf::{PerfCounters, PerfCounts};
+use crate::rusage::Rusage;
+
+// The measurement loop shared by every benchmark in the native and wasi
+// harness. It lives in the library so the tests can check that the work it
+// measures is not optimized away.
+
+// Calls of `Instant::now()` averaged to get the timer overhead
+const OVERHEAD_CALLS: u32 = 10_000;
+// Clock ticks observed to get the timer resolution, keeping the smallest
+const RESOLUTION_TICKS: u32 = 100;
+// A sample must last this many times the resolution or the overhead of the
+// timer, whichever is larger, so neither adds more than 0.1% to it
+const SAMPLE_TIMER_RATIO: u32 = 1000;
+// ...and never less than this
+const MIN_SAMPLE_TIME: Duration = Duration::from_millis(1);
+// Samples taken before the adaptive mode starts checking the interval
+const MIN_ADAPTIVE_SAMPLES: usize = 10;
+// Working out the interval sorts the times, so it is only checked again once
+// there are 1/ADAPTIVE_CHECK_DIVISOR more of them: checking every sample would
+// grow quadratically and eat into the budget
+const ADAPTIVE_CHECK_DIVISOR: usize = 16;
+// Standard normal quantile for the 95% confidence intervals
+const Z_95: f64 = 1.96;
+
+/// Resolution and overhead of `Instant`, measured when the harness starts.
+/// Both vary a lot between runtimes: wasi clocks can be much coarser than the
+/// native ones.
+#[derive(Clone, Copy, Debug)]
+pub struct TimerCalibration {
+    /// Smallest nonzero difference seen between two readings of the clock.
+    pub resolution: Duration,
+    /// Average time taken by one `Instant::now()`.
+    pub overhead: Duration,
+}
+
+impl TimerCalibration {
+    pub fn measure() -> TimerCalibration {
+        let start = Instant::now();
+        for _ in 0..OVERHEAD_CALLS {
+            black_box(Instant::now());
+        }
+        let overhead = start.elapsed() / OVERHEAD_CALLS;
+
+        let resolution = (0..RESOLUTION_TICKS)
+            .map(|_| {
+                let start = Instant::now();
+                loop {
+                    let elapsed = start.elapsed();
+                    if !elapsed.is_zero() {
+                        break elapsed;
+                    }
+                }
+            })
+            .min()
+            .unwrap();
+
+        TimerCalibration {
+            resolution,
+            overhead,
+        }
+    }
+
+    /// Shortest sample the timer measures accurately.
+    pub fn min_sample_time(&self) -> Duration {
+        (self.resolution.max(self.overhead) * SAMPLE_TIMER_RATIO).max(MIN_SAMPLE_TIME)
+    }
+}
+
+/// Ends the warmup once the median time of the last `window` calls is within
+/// `tolerance` of the median of the `window` calls before them. JIT tiers, a
+/// cold cache or the first memory growth all show up as a drift between the
+/// two.
+#[derive(Clone, Copy, Debug)]
+pub struct SteadyState {
+    pub window: usize,
+    pub tolerance: f64,
+}
+
+impl SteadyState {
+    pub fn reached(&self, times: &[u128]) -> bool {
+        if times.len() < 2 * self.window {
+            return false;
+        }
+        let (previous, last) = times[times.len() - 2 * self.window..].split_at(self.window);
+        let (previous, last) = (median(previous), median(last));
+        (last - previous).abs() <= self.tolerance * previous
+    }
+}
+
+/// Why `Harness::measure` ended the warmup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarmupEnd {
+    /// The times reached a steady state.
+    Steady,
+    /// It made `warmup_iterations` calls.
+    #[default]
+    Iterations,
+    /// It ran for `warmup_time`.
+    Time,
+}
+
+impl WarmupEnd {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarmupEnd::Steady => "steady",
+            WarmupEnd::Iterations => "iterations",
+            WarmupEnd::Time => "time",
+        }
+    }
+}
+
+/// Median of some times, which must not be empty.
+pub fn median(values: &[u128]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    median_of_sorted(&sorted)
+}
+
+fn median_of_sorted(sorted: &[u128]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2] as f64
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0
+    }
+}
+
+/// Settings of the adaptive mode, which takes samples until the confidence
+/// interval of the median is narrow enough instead of a fixed number of them.
+#[derive(Clone, Copy, Debug)]
+pub struct Adaptive {
+    /// Stop once the 95% confidence interval of the median is within
+    /// ±`target` of it, e.g. 0.01 for ±1%...
+    pub target: f64,
+    /// ...or once sampling has taken this long.
+    pub budget: Duration,
+}
+
+/// Why `Harness::measure` stopped taking samples.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StopReason {
+    /// It took the number of executions asked for.
+    #[default]
+    Executions,
+    /// The confidence interval reached the adaptive target.
+    Converged,
+    /// The adaptive time budget ran out first.
+    Budget,
+}
+
+impl StopReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StopReason::Executions => "executions",
+            StopReason::Converged => "converged",
+            StopReason::Budget => "budget",
+        }
+    }
+}
+
+/// Median of a set of times with its 95% confidence interval, in nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MedianInterval {
+    pub median: f64,
+    pub low: u128,
+    pub high: u128,
+}
+
+impl MedianInterval {
+    /// Distribution-free interval, between the order statistics whose ranks
+    /// bound the median with 95% confidence (normal approximation to the
+    /// binomial). `None` when there are too few times for it.
+    pub fn of(times: &[u128]) -> Option<MedianInterval> {
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let spread = Z_95 * (n as f64).sqrt() / 2.0;
+        // 1-based ranks
+        let low_rank = (n as f64 / 2.0 - spread).floor();
+        let high_rank = (n as f64 / 2.0 + 1.0 + spread).ceil();
+        if low_rank < 1.0 || high_rank > n as f64 {
+            return None;
+        }
+
+        Some(MedianInterval {
+            median: median_of_sorted(&sorted),
+            low: sorted[low_rank as usize - 1],
+            high: sorted[high_rank as usize - 1],
+        })
+    }
+
+    /// Half the width of the interval relative to the median. Infinite when
+    /// the median is 0, i.e. below the timer's resolution, where no width is
+    /// narrow enough to mean anything.
+    pub fn relative_width(&self) -> f64 {
+        if self.median == 0.0 {
+            return f64::INFINITY;
+        }
+        (self.high - self.low) as f64 / 2.0 / self.median
+    }
+}
+
+/// How the times of a benchmark run in several processes spread, in squared
+/// nanoseconds. A between-process variance well above the within-process one
+/// means each process settles on its own speed, from its heap layout, the
+/// addresses it got or the core it ran on, and that one process isn't enough.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProcessVariance {
+    /// Sample variance of the mean time of every process.
+    pub between: f64,
+    /// Mean of the sample variance of the times in every process.
+    pub within: f64,
+}
+
+impl ProcessVariance {
+    /// `None` with fewer than two processes, or if any of them has fewer than
+    /// two times.
+    pub fn of(processes: &[Vec<u128>]) -> Option<ProcessVariance> {
+        if processes.len() < 2 || processes.iter().any(|times| times.len() < 2) {
+            return None;
+        }
+        let floats: Vec<Vec<f64>> = processes
+            .iter()
+            .map(|times| times.iter().map(|&time| time as f64).collect())
+            .collect();
+        let means: Vec<f64> = floats.iter().map(|times| mean(times)).collect();
+        let within = floats
+            .iter()
+            .zip(&means)
+            .map(|(times, &mean)| variance(times, mean))
+            .sum::<f64>()
+            / processes.len() as f64;
+
+        Some(ProcessVariance {
+            between: variance(&means, mean(&means)),
+            within,
+        })
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Sample variance, with Bessel's correction
+fn variance(values: &[f64], mean: f64) -> f64 {
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (values.len() - 1) as f64
+}
+
+/// Settings of the measurement loop.
+#[derive(Clone, Debug)]
+pub struct Harness {
+    /// Warmup ends after this many calls...
+    pub warmup_iterations: u32,
+    /// ...or after this long, whichever comes first...
+    pub warmup_time: Duration,
+    /// ...or, if set, once the times are steady.
+    pub steady_state: Option<SteadyState>,
+    pub timer: TimerCalibration,
+    /// Calls shorter than this are batched, timing as many calls together as
+    /// needed to reach it. `Duration::ZERO` times every call on its own.
+    pub min_sample_time: Duration,
+    /// When set, `executions` is ignored and samples are taken adaptively.
+    pub adaptive: Option<Adaptive>,
+    /// Read hardware counters around every sample, where the system has them.
+    pub perf_counters: bool,
+}
+
+impl Default for Harness {
+    /// Calibrates the timer, which takes a few milliseconds.
+    fn default() -> Harness {
+        let timer = TimerCalibration::measure();
+        Harness {
+            warmup_iterations: 100,
+            warmup_time: Duration::from_secs(30),
+            steady_state: Some(SteadyState {
+                window: 10,
+                tolerance: 0.05,
+            }),
+            timer,
+            min_sample_time: timer.min_sample_time(),
+            adaptive: None,
+            perf_counters: false,
+        }
+    }
+}
+
+/// Result of `Harness::measure`.
+#[derive(Clone, Debug, Default)]
+pub struct Measurement {
+    /// Time of every warmup call, in nanoseconds. Warmup calls are never
+    /// batched.
+    pub warmup_times: Vec<u128>,
+    pub warmup_end: WarmupEnd,
+    /// Calls timed together in every sample.
+    pub batch: u64,
+    /// Time of every sample divided by its batch, so the time of one call, in
+    /// nanoseconds.
+    pub times: Vec<u128>,
+    /// Heap used by one call in every sample, all zeros unless
+    /// `heap::CountingAlloc` is the global allocator.
+    pub heap: Vec<HeapStats>,
+    /// Hardware counts for one call in every sample, empty unless the harness
+    /// asked for them and could open some counters.
+    pub perf: Vec<PerfCounts>,
+    /// Faults and context switches during every sample, as a whole, empty
+    /// where `getrusage` isn't read.
+    pub rusage: Vec<Rusage>,
+    pub stop_reason: StopReason,
+}
+
+/// Likely cause of an outlier, from what `getrusage` saw during its sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutlierCause {
+    MajorFaults,
+    MinorFaults,
+    /// More involuntary context switches than usual.
+    Preempted,
+    /// More voluntary context switches than usual.
+    Blocked,
+    Unknown,
+}
+
+impl OutlierCause {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutlierCause::MajorFaults => "major_faults",
+            OutlierCause::MinorFaults => "minor_faults",
+            OutlierCause::Preempted => "preempted",
+            OutlierCause::Blocked => "blocked",
+            OutlierCause::Unknown => "unknown",
+        }
+    }
+}
+
+/// A sample slower than the upper Tukey fence, Q3 + 1.5 IQR.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outlier {
+    pub sample: usize,
+    pub cause: OutlierCause,
+}
+
+type RusageCounter = fn(&Rusage) -> u64;
+
+// Counters that can explain an outlier, the most likely cause first
+const OUTLIER_CAUSES: [(OutlierCause, RusageCounter); 4] = [
+    (OutlierCause::MajorFaults, |rusage| rusage.major_faults),
+    (OutlierCause::MinorFaults, |rusage| rusage.minor_faults),
+    (OutlierCause::Preempted, |rusage| {
+        rusage.involuntary_switches
+    }),
+    (OutlierCause::Blocked, |rusage| rusage.voluntary_switches),
+];
+
+impl Measurement {
+    pub fn outliers(&self) -> Vec<Outlier> {
+        let mut sorted = self.times.clone();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        if n < 4 {
+            return Vec::new();
+        }
+        let (q1, q3) = (sorted[n / 4], sorted[3 * n / 4]);
+        let fence = q3 + (q3 - q1) * 3 / 2;
+
+        // A counter explains an outlier when it is above its median in the
+        // outlier's sample
+        let medians = OUTLIER_CAUSES.map(|(_, counter)| {
+            let values: Vec<u128> = self.rusage.iter().map(|r| counter(r) as u128).collect();
+            if values.is_empty() {
+                0.0
+            } else {
+                median(&values)
+            }
+        });
+        let cause = |rusage: &Rusage| {
+            OUTLIER_CAUSES
+                .iter()
+                .zip(medians)
+                .find(|&(&(_, counter), median)| counter(rusage) as f64 > median)
+                .map(|(&(cause, _), _)| cause)
+        };
+
+        self.times
+            .iter()
+            .enumerate()
+            .filter(|&(_, &time)| time > fence)
+            .map(|(sample, _)| Outlier {
+                sample,
+                cause: self
+                    .rusage
+                    .get(sample)
+                    .and_then(cause)
+                    .unwrap_or(OutlierCause::Unknown),
+            })
+            .collect()
+    }
+}
+
+// One timed batch of calls
+struct Sample {
+    elapsed: Duration,
+    heap: HeapStats,
+    perf: Option<PerfCounts>,
+    rusage: Option<Rusage>,
+}
+
+impl Harness {
+    /// Calls `run` through the warmup and then `executions` more times, timing
+    /// each call. Calls shorter than `min_sample_time` are batched, and the
+    /// `executions` samples then take `batch` calls each. In adaptive mode the
+    /// samples go on until the median is known precisely enough or the budget
+    /// runs out, whatever `executions` says.
+    ///
+    /// `run` gets `input` and `state` through `black_box`, and its output goes
+    /// through `black_box` before the timer stops, so the compiler can neither
+    /// precompute the call nor drop it. The output is then given to `after`,
+    /// outside of the timed region, to check it or to reset `state` for the
+    /// next call. In a batch `after` only runs once the whole batch is timed,
+    /// so kernels that need `state` reset between calls must not be batched.
+    pub fn measure<I: ?Sized, S, O>(
+        &self,
+        executions: u64,
+        name: &str,
+        input: &I,
+        state: &mut S,
+        mut run: impl FnMut(&I, &mut S) -> O,
+        mut after: impl FnMut(&mut S, O),
+    ) -> Measurement {
+        let mut measurement = Measurement::default();
+
+        // Times `batch` calls together. Their outputs are kept in `outputs`,
+        // allocated before the timer starts, until `after` gets them, and the
+        // heap they use is counted without them.
+        let mut outputs = Vec::new();
+        let mut sample = |batch: u64, perf: Option<&PerfCounters>| {
+            outputs.reserve(batch as usize);
+
+            let heap = HeapSnapshot::take();
+            let rusage = Rusage::now();
+            if let Some(perf) = perf {
+                perf.start();
+            }
+            let start = Instant::now();
+            for _ in 0..batch {
+                outputs.push(black_box(run(black_box(input), black_box(&mut *state))));
+            }
+            let elapsed = start.elapsed();
+            let perf = perf.map(|perf| perf.stop(batch));
+            let rusage = rusage
+                .zip(Rusage::now())
+                .map(|(before, after)| after.since(&before));
+            let heap = heap.stats(batch);
+
+            for output in outputs.drain(..) {
+                after(state, output);
+            }
+            Sample {
+                elapsed,
+                heap,
+                perf,
+                rusage,
+            }
+        };
+
+        eprintln!("Warming up {}...", name);
+        let warmup_begin = Instant::now();
+        measurement.warmup_end = loop {
+            let times = &measurement.warmup_times;
+            if self
+                .steady_state
+                .is_some_and(|steady| steady.reached(times))
+            {
+                break WarmupEnd::Steady;
+            } else if times.len() >= self.warmup_iterations as usize {
+                break WarmupEnd::Iterations;
+            } else if warmup_begin.elapsed() >= self.warmup_time {
+                break WarmupEnd::Time;
+            }
+            let elapsed = sample(1, None).elapsed;
+            measurement.warmup_times.push(elapsed.as_nanos());
+        };
+
+        // Double the batch until it is long enough to time
+        measurement.batch = 1;
+        while sample(measurement.batch, None).elapsed < self.min_sample_time {
+            measurement.batch *= 2;
+        }
+        eprintln!(
+            "Warmup done in {} iterations ({}), executing {} times in batches of {}...",
+            measurement.warmup_times.len(),
+            measurement.warmup_end.name(),
+            executions,
+            measurement.batch
+        );
+
+        let perf = self.perf_counters.then(PerfCounters::open).flatten();
+        if self.perf_counters && perf.is_none() {
+            eprintln!("No hardware counters available, measuring times only.");
+        }
+
+        let sampling_begin = Instant::now();
+        let mut next_check = MIN_ADAPTIVE_SAMPLES;
+        loop {
+            if let Some(stop_reason) = self.should_stop(
+                executions,
+                &measurement.times,
+                &mut next_check,
+                sampling_begin,
+            ) {
+                measurement.stop_reason = stop_reason;
+                break;
+            }
+            let sample = sample(measurement.batch, perf.as_ref());
+            measurement
+                .times
+                .push(sample.elapsed.as_nanos() / measurement.batch as u128);
+            measurement.heap.push(sample.heap);
+            measurement.perf.extend(sample.perf);
+            measurement.rusage.extend(sample.rusage);
+        }
+
+        eprintln!(
+            "Took {} samples, stopped by {}.",
+            measurement.times.len(),
+            measurement.stop_reason.name()
+        );
+        measurement
+    }
+
+    // `next_check` is the number of times at which the adaptive mode next
+    // works out the interval
+    fn should_stop(
+        &self,
+        executions: u64,
+        times: &[u128],
+        next_check: &mut usize,
+        sampling_begin: Instant,
+    ) -> Option<StopReason> {
+        let Some(adaptive) = self.adaptive else {
+            return (times.len() as u64 >= executions).then_some(StopReason::Executions);
+        };
+        let check = times.len() >= *next_check;
+        if check {
+            *next_check = times.len() + (times.len() / ADAPTIVE_CHECK_DIVISOR).max(1);
+        }
+        if check
+            && MedianInterval::of(times)
+                .is_some_and(|interval| interval.relative_width() <= adaptive.target)
+        {
+            Some(StopReason::Converged)
+        } else if sampling_begin.elapsed() >= adaptive.budget {
+            Some(StopReason::Budget)
+        } else {
+            None
+        }
+    }
+}
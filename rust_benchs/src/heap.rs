@@ -0,0 +1,99 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Heap usage of the kernels. The benchmark binary installs `CountingAlloc` as
+// its global allocator when built with the `count-allocs` feature; otherwise
+// the counters stay at zero, so builds that don't count pay nothing.
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting allocations, bytes allocated and the live
+/// heap. A `realloc` counts as an allocation of the new size.
+pub struct CountingAlloc;
+
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Heap used by one call of a kernel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// Largest amount of heap live at once, above what was live before the
+    /// call.
+    pub peak_live: u64,
+}
+
+/// Counters read before some calls, to get their `HeapStats` afterwards.
+#[derive(Clone, Copy, Debug)]
+pub struct HeapSnapshot {
+    allocations: u64,
+    bytes: u64,
+    live: u64,
+}
+
+impl HeapSnapshot {
+    /// Reads the counters and restarts the peak from the current live heap.
+    pub fn take() -> HeapSnapshot {
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+        HeapSnapshot {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            live,
+        }
+    }
+
+    /// Heap used since the snapshot by `calls` calls, with the allocations
+    /// and bytes averaged over them. The peak is over all of them.
+    pub fn stats(&self, calls: u64) -> HeapStats {
+        HeapStats {
+            allocations: (ALLOCATIONS.load(Ordering::Relaxed) - self.allocations) / calls,
+            bytes: (BYTES.load(Ordering::Relaxed) - self.bytes) / calls,
+            peak_live: PEAK.load(Ordering::Relaxed).saturating_sub(self.live),
+        }
+    }
+}
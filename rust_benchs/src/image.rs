@@ -0,0 +1,283 @@
+use std::slice;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+const BOX_KERNEL: [i32; 9] = [1, 1, 1, 1, 1, 1, 1, 1, 1];
+const BOX_DIVISOR: i32 = 9;
+
+// 5x5 binomial approximation of a Gaussian
+const GAUSSIAN_KERNEL: [i32; 25] = [
+    1, 4, 6, 4, 1, //
+    4, 16, 24, 16, 4, //
+    6, 24, 36, 24, 6, //
+    4, 16, 24, 16, 4, //
+    1, 4, 6, 4, 1,
+];
+const GAUSSIAN_DIVISOR: i32 = 256;
+
+const SOBEL_X: [i32; 9] = [-1, 0, 1, -2, 0, 2, -1, 0, 1];
+const SOBEL_Y: [i32; 9] = [-1, -2, -1, 0, 0, 0, 1, 2, 1];
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub enum ImageFilter {
+    Box = 0,
+    Gaussian = 1,
+    /// Gradient magnitude as |gx| + |gy|, for the u8 and the f32 images alike,
+    /// so both do the same arithmetic and only the number type differs.
+    Sobel = 2,
+}
+
+impl ImageFilter {
+    pub fn from_name(name: &str) -> Option<ImageFilter> {
+        match name {
+            "box" => Some(ImageFilter::Box),
+            "gaussian" => Some(ImageFilter::Gaussian),
+            "sobel" => Some(ImageFilter::Sobel),
+            _ => None,
+        }
+    }
+}
+
+/// Generates a grayscale noise image of `width` x `height` pixels.
+#[wasm_bindgen]
+pub fn image_generate(width: u32, height: u32, seed: u32) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    (0..width as usize * height as usize)
+        .map(|_| rng.random())
+        .collect()
+}
+
+/// Converts an 8-bit image to floats in [0, 1].
+#[wasm_bindgen]
+pub fn image_to_f32(image: &[u8]) -> Vec<f32> {
+    image.iter().map(|&p| p as f32 / 255.0).collect()
+}
+
+// Visits the neighbourhood of (x, y) covered by the square `kernel`, passing
+// each pixel with its weight to `add`. Pixels outside the image are clamped to
+// the nearest edge pixel.
+fn convolve_at<T: Copy>(
+    input: &[T],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    kernel: &[i32],
+    mut add: impl FnMut(T, i32),
+) {
+    let size = kernel.len().isqrt();
+    let radius = size / 2;
+    for ky in 0..size {
+        let sy = (y + ky).saturating_sub(radius).min(height - 1);
+        for kx in 0..size {
+            let sx = (x + kx).saturating_sub(radius).min(width - 1);
+            add(input[sy * width + sx], kernel[ky * size + kx]);
+        }
+    }
+}
+
+fn convolve_u8(
+    input: &[u8],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    kernel: &[i32],
+) -> i32 {
+    let mut sum = 0;
+    convolve_at(input, width, height, x, y, kernel, |p, k| {
+        sum += p as i32 * k
+    });
+    sum
+}
+
+fn convolve_f32(
+    input: &[f32],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    kernel: &[i32],
+) -> f32 {
+    let mut sum = 0.0;
+    convolve_at(input, width, height, x, y, kernel, |p, k| {
+        sum += p * k as f32
+    });
+    sum
+}
+
+#[wasm_bindgen]
+pub fn image_filter_u8(
+    input: &[u8],
+    output: &mut [u8],
+    width: u32,
+    height: u32,
+    filter: ImageFilter,
+) {
+    let (width, height) = (width as usize, height as usize);
+    assert_eq!(
+        input.len(),
+        width * height,
+        "input image has incorrect size"
+    );
+    assert_eq!(
+        output.len(),
+        width * height,
+        "output image has incorrect size"
+    );
+
+    for y in 0..height {
+        for x in 0..width {
+            let value = match filter {
+                ImageFilter::Box => {
+                    convolve_u8(input, width, height, x, y, &BOX_KERNEL) / BOX_DIVISOR
+                }
+                ImageFilter::Gaussian => {
+                    convolve_u8(input, width, height, x, y, &GAUSSIAN_KERNEL) / GAUSSIAN_DIVISOR
+                }
+                ImageFilter::Sobel => {
+                    let gx = convolve_u8(input, width, height, x, y, &SOBEL_X);
+                    let gy = convolve_u8(input, width, height, x, y, &SOBEL_Y);
+                    gx.abs() + gy.abs()
+                }
+            };
+            output[y * width + x] = value.clamp(0, 255) as u8;
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn image_filter_f32(
+    input: &[f32],
+    output: &mut [f32],
+    width: u32,
+    height: u32,
+    filter: ImageFilter,
+) {
+    let (width, height) = (width as usize, height as usize);
+    assert_eq!(
+        input.len(),
+        width * height,
+        "input image has incorrect size"
+    );
+    assert_eq!(
+        output.len(),
+        width * height,
+        "output image has incorrect size"
+    );
+
+    for y in 0..height {
+        for x in 0..width {
+            output[y * width + x] = match filter {
+                ImageFilter::Box => {
+                    convolve_f32(input, width, height, x, y, &BOX_KERNEL) / BOX_DIVISOR as f32
+                }
+                ImageFilter::Gaussian => {
+                    convolve_f32(input, width, height, x, y, &GAUSSIAN_KERNEL)
+                        / GAUSSIAN_DIVISOR as f32
+                }
+                ImageFilter::Sobel => {
+                    let gx = convolve_f32(input, width, height, x, y, &SOBEL_X);
+                    let gy = convolve_f32(input, width, height, x, y, &SOBEL_Y);
+                    gx.abs() + gy.abs()
+                }
+            };
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn image_filter_u8_in_memory(
+    input_ptr: *const u8,
+    output_ptr: *mut u8,
+    width: u32,
+    height: u32,
+    filter: ImageFilter,
+) {
+    let len = width as usize * height as usize;
+    let input = unsafe { slice::from_raw_parts(input_ptr, len) };
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, len) };
+    image_filter_u8(input, output, width, height, filter);
+}
+
+#[wasm_bindgen]
+pub fn image_filter_f32_in_memory(
+    input_ptr: *const f32,
+    output_ptr: *mut f32,
+    width: u32,
+    height: u32,
+    filter: ImageFilter,
+) {
+    let len = width as usize * height as usize;
+    let input = unsafe { slice::from_raw_parts(input_ptr, len) };
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, len) };
+    image_filter_f32(input, output, width, height, filter);
+}
+
+/// Generates a Game of Life board where about a third of the cells are alive.
+#[wasm_bindgen]
+pub fn life_generate(width: u32, height: u32, seed: u32) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    (0..width as usize * height as usize)
+        .map(|_| rng.random_ratio(1, 3) as u8)
+        .collect()
+}
+
+/// Runs `steps` Game of Life generations on a board that wraps around at the
+/// edges. Cells are 1 when alive and 0 when dead; the final board is written
+/// to `output`.
+#[wasm_bindgen]
+pub fn life_steps(input: &[u8], output: &mut [u8], width: u32, height: u32, steps: u32) {
+    let (width, height) = (width as usize, height as usize);
+    assert_eq!(
+        input.len(),
+        width * height,
+        "input board has incorrect size"
+    );
+    assert_eq!(
+        output.len(),
+        width * height,
+        "output board has incorrect size"
+    );
+
+    output.copy_from_slice(input);
+    let mut next = vec![0u8; width * height];
+    for _ in 0..steps {
+        for y in 0..height {
+            let up = (y + height - 1) % height;
+            let down = (y + 1) % height;
+            for x in 0..width {
+                let left = (x + width - 1) % width;
+                let right = (x + 1) % width;
+                let neighbours = output[up * width + left]
+                    + output[up * width + x]
+                    + output[up * width + right]
+                    + output[y * width + left]
+                    + output[y * width + right]
+                    + output[down * width + left]
+                    + output[down * width + x]
+                    + output[down * width + right];
+                let alive = output[y * width + x] == 1;
+                next[y * width + x] = (neighbours == 3 || (alive && neighbours == 2)) as u8;
+            }
+        }
+        output.copy_from_slice(&next);
+    }
+}
+
+#[wasm_bindgen]
+pub fn life_in_memory(
+    input_ptr: *const u8,
+    output_ptr: *mut u8,
+    width: u32,
+    height: u32,
+    steps: u32,
+) {
+    let len = width as usize * height as usize;
+    let input = unsafe { slice::from_raw_parts(input_ptr, len) };
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, len) };
+    life_steps(input, output, width, height, steps);
+}
@@ -0,0 +1,146 @@
+use std::slice;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+// Integer division and remainder trap in wasm on a zero divisor and on
+// MIN / -1, so engines guard every `div`/`rem` that native code runs bare.
+// Divisors below are forced to be odd, so they are never zero, but the
+// compiler can't prove the overflow case away.
+
+/// Generates `len` random values for the integer arithmetic kernels.
+#[wasm_bindgen]
+pub fn intarith_data(len: usize, seed: u32) -> Vec<u32> {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    (0..len).map(|_| rng.random()).collect()
+}
+
+/// Divides every value by the next one as `i32`, adding quotients and
+/// remainders. Divisors are taken modulo 2^k for a varying k, so quotients of
+/// every size show up.
+#[wasm_bindgen]
+pub fn intarith_div_i32(data: &[u32]) -> u32 {
+    let mut sum: i32 = 0;
+    for (i, pair) in data.windows(2).enumerate() {
+        let dividend = pair[0] as i32;
+        let divisor = (pair[1] as i32 >> (i % 31)) | 1;
+        sum = sum
+            .wrapping_add(dividend.wrapping_div(divisor))
+            .wrapping_add(dividend.wrapping_rem(divisor));
+    }
+    sum as u32
+}
+
+/// Same as `intarith_div_i32` with 64-bit dividends made of two values.
+#[wasm_bindgen]
+pub fn intarith_div_i64(data: &[u32]) -> u32 {
+    let mut sum: i64 = 0;
+    for (i, triple) in data.windows(3).enumerate() {
+        let dividend = ((triple[0] as u64) << 32 | triple[1] as u64) as i64;
+        let divisor = (triple[2] as i32 as i64 >> (i % 31)) | 1;
+        sum = sum
+            .wrapping_add(dividend.wrapping_div(divisor))
+            .wrapping_add(dividend.wrapping_rem(divisor));
+    }
+    (sum ^ (sum >> 32)) as u32
+}
+
+/// Multiply-add hash over the values as `i32`, restarting from the value
+/// whenever `checked_mul` or `checked_add` overflows. Returns the hash mixed
+/// with the number of overflows.
+#[wasm_bindgen]
+pub fn intarith_checked(data: &[u32]) -> u32 {
+    let mut hash: i32 = 1;
+    let mut overflows: u32 = 0;
+    for &value in data {
+        // Keep the multiplier small so that some steps don't overflow
+        let value = value as i32;
+        hash = match hash
+            .checked_mul(value >> 24 | 1)
+            .and_then(|product| product.checked_add(value))
+        {
+            Some(next) => next,
+            None => {
+                overflows += 1;
+                value
+            }
+        };
+    }
+    (hash as u32).wrapping_mul(31).wrapping_add(overflows)
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    a * b % modulus
+}
+
+fn pow_mod(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+// Miller-Rabin with the bases 2, 7 and 61, which is exact for every u32
+fn is_prime(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in [2, 3, 5, 7, 61] {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let n = n as u64;
+    let trailing = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> trailing;
+    'bases: for base in [2, 7, 61] {
+        let mut x = pow_mod(base, odd, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..trailing {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Counts the primes among the values with Miller-Rabin, where almost all the
+/// time goes to `u64` remainders in modular exponentiation.
+#[wasm_bindgen]
+pub fn intarith_count_primes(data: &[u32]) -> u32 {
+    data.iter().filter(|&&n| is_prime(n)).count() as u32
+}
+
+#[wasm_bindgen]
+pub fn intarith_div_i32_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    intarith_div_i32(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn intarith_div_i64_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    intarith_div_i64(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn intarith_checked_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    intarith_checked(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn intarith_count_primes_in_memory(data_ptr: *const u32, len: usize) -> u32 {
+    intarith_count_primes(unsafe { slice::from_raw_parts(data_ptr, len) })
+}
@@ -0,0 +1,135 @@
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::text::{fnv1a, str_in_memory};
+
+// Characters that need escaping or multi-byte encoding when serialized
+const SPECIAL_CHARS: [char; 8] = ['"', '\\', '\n', '\t', '/', 'é', '中', '😀'];
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    records: Vec<Record>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    id: u64,
+    name: String,
+    active: bool,
+    tags: Vec<String>,
+    values: Vec<f64>,
+    child: Option<Box<Record>>,
+}
+
+// How each record of a generated document looks like: nesting depth through
+// `child`, length of `values`, and number and length of `tags`.
+struct Shape {
+    depth: u32,
+    values: usize,
+    tags: usize,
+    tag_len: usize,
+}
+
+fn shape(name: &str) -> Shape {
+    match name {
+        // serde_json refuses to nest deeper than 128 levels
+        "nested" => Shape {
+            depth: 60,
+            values: 2,
+            tags: 1,
+            tag_len: 8,
+        },
+        "wide" => Shape {
+            depth: 0,
+            values: 256,
+            tags: 0,
+            tag_len: 0,
+        },
+        "strings" => Shape {
+            depth: 0,
+            values: 0,
+            tags: 32,
+            tag_len: 48,
+        },
+        _ => panic!("unknown json document shape: {}", name),
+    }
+}
+
+fn random_string(rng: &mut StdRng, max_len: usize) -> String {
+    let len = rng.random_range(1..=max_len);
+    (0..len)
+        .map(|_| {
+            if rng.random_ratio(1, 8) {
+                *SPECIAL_CHARS.choose(rng).unwrap()
+            } else {
+                rng.random_range(b'a'..=b'z') as char
+            }
+        })
+        .collect()
+}
+
+fn random_record(rng: &mut StdRng, shape: &Shape, depth: u32) -> Record {
+    Record {
+        // Kept below 2^53 so that JavaScript numbers can hold it exactly
+        id: rng.random_range(0..1 << 53),
+        name: random_string(rng, 16),
+        active: rng.random(),
+        tags: (0..shape.tags)
+            .map(|_| random_string(rng, shape.tag_len))
+            .collect(),
+        values: (0..shape.values)
+            .map(|_| rng.random::<f64>() * 1000.0)
+            .collect(),
+        child: if depth < shape.depth {
+            Some(Box::new(random_record(rng, shape, depth + 1)))
+        } else {
+            None
+        },
+    }
+}
+
+/// Generates a JSON document with `records` records. `shape` is one of
+/// `"nested"` (deeply nested objects), `"wide"` (long number arrays) or
+/// `"strings"` (many strings with escapes and non-ASCII characters).
+#[wasm_bindgen]
+pub fn json_document(records: u32, shape_name: &str, seed: u32) -> String {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let shape = shape(shape_name);
+    let document = Document {
+        records: (0..records)
+            .map(|_| random_record(&mut rng, &shape, 0))
+            .collect(),
+    };
+    serde_json::to_string(&document).unwrap()
+}
+
+/// Parses the document into a `serde_json::Value` and serializes it back,
+/// returning a checksum of the output.
+#[wasm_bindgen]
+pub fn json_value_roundtrip(document: &str) -> u32 {
+    let value: serde_json::Value = serde_json::from_str(document).unwrap();
+    let output = serde_json::to_string(&value).unwrap();
+    fnv1a(output.as_bytes())
+}
+
+/// Same as `json_value_roundtrip`, but going through the typed structs the
+/// document was generated from.
+#[wasm_bindgen]
+pub fn json_typed_roundtrip(document: &str) -> u32 {
+    let value: Document = serde_json::from_str(document).unwrap();
+    let output = serde_json::to_string(&value).unwrap();
+    fnv1a(output.as_bytes())
+}
+
+#[wasm_bindgen]
+pub fn json_value_roundtrip_in_memory(document_ptr: *const u8, len: usize) -> u32 {
+    json_value_roundtrip(unsafe { str_in_memory(document_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn json_typed_roundtrip_in_memory(document_ptr: *const u8, len: usize) -> u32 {
+    json_typed_roundtrip(unsafe { str_in_memory(document_ptr, len) })
+}
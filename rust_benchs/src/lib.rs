@@ -1,11 +1,36 @@
+// The `*_in_memory` exports and the `dealloc` helpers take raw pointers from
+// JavaScript, which is the whole point of them, so they stay safe to call from
+// the JS side.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 use std::slice;
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use wasm_bindgen::prelude::*;
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use console_error_panic_hook;
 
+pub mod abstraction;
+pub mod bignum;
+pub mod dynprog;
+pub mod graph;
+pub mod harness;
+pub mod heap;
+pub mod image;
+pub mod intarith;
+pub mod json;
+pub mod memory;
+pub mod perf;
+pub mod prng;
+pub mod raytracer;
+pub mod rusage;
+pub mod sync;
+pub mod text;
+
+use prng::{Pcg32, Prng, SplitMix64, Xorshift64};
+
 #[wasm_bindgen]
 pub fn fibonacci(n: u32) -> u32 {
     match n {
@@ -15,6 +40,130 @@ pub fn fibonacci(n: u32) -> u32 {
     }
 }
 
+/// Largest `n` for which the n-th Fibonacci number fits in a `u64`.
+pub const FIBONACCI_MAX_N: u32 = 93;
+
+// The u64 variants below return `None` instead of overflowing, either because
+// `n` is above FIBONACCI_MAX_N or because an addition or product overflowed.
+
+/// Same naive recursion as `fibonacci`, but over `u64` with checked additions.
+/// `n` is checked once here, so the recursion itself only pays for the calls
+/// and the additions.
+#[wasm_bindgen]
+pub fn fibonacci_recursive(n: u32) -> Option<u64> {
+    fn fib(n: u32) -> Option<u64> {
+        match n {
+            0 => Some(0),
+            1 => Some(1),
+            _ => fib(n - 1)?.checked_add(fib(n - 2)?),
+        }
+    }
+
+    if n > FIBONACCI_MAX_N {
+        return None;
+    }
+    fib(n)
+}
+
+#[wasm_bindgen]
+pub fn fibonacci_iterative(n: u32) -> Option<u64> {
+    if n > FIBONACCI_MAX_N {
+        return None;
+    }
+    if n == 0 {
+        return Some(0);
+    }
+    let (mut a, mut b): (u64, u64) = (0, 1);
+    for _ in 1..n {
+        (a, b) = (b, a.checked_add(b)?);
+    }
+    Some(b)
+}
+
+/// Recursion with a memo table. The table is allocated anew on every call,
+/// and that allocation is part of what this variant measures: the cost of
+/// keeping state for the calls, rather than the calls alone.
+#[wasm_bindgen]
+pub fn fibonacci_memoized(n: u32) -> Option<u64> {
+    fn fib(n: usize, memo: &mut [Option<u64>]) -> Option<u64> {
+        if n < 2 {
+            return Some(n as u64);
+        }
+        if let Some(value) = memo[n] {
+            return Some(value);
+        }
+        let value = fib(n - 1, memo)?.checked_add(fib(n - 2, memo)?)?;
+        memo[n] = Some(value);
+        Some(value)
+    }
+
+    if n > FIBONACCI_MAX_N {
+        return None;
+    }
+    let mut memo = vec![None; n as usize + 1];
+    fib(n as usize, &mut memo)
+}
+
+/// Raises [[1, 1], [1, 0]] to the (n - 1)-th power by repeated squaring. The
+/// top-left entry of the result is F(n).
+#[wasm_bindgen]
+pub fn fibonacci_matrix(n: u32) -> Option<u64> {
+    type Matrix = [[u64; 2]; 2];
+
+    fn multiply(a: &Matrix, b: &Matrix) -> Option<Matrix> {
+        let mut c = [[0; 2]; 2];
+        for i in 0..2 {
+            for j in 0..2 {
+                c[i][j] = a[i][0]
+                    .checked_mul(b[0][j])?
+                    .checked_add(a[i][1].checked_mul(b[1][j])?)?;
+            }
+        }
+        Some(c)
+    }
+
+    if n > FIBONACCI_MAX_N {
+        return None;
+    }
+    if n == 0 {
+        return Some(0);
+    }
+    let mut result: Matrix = [[1, 0], [0, 1]];
+    let mut square: Matrix = [[1, 1], [1, 0]];
+    let mut exponent = n - 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(&result, &square)?;
+        }
+        exponent >>= 1;
+        // Skip the last squaring, it would overflow for n close to the maximum
+        if exponent > 0 {
+            square = multiply(&square, &square)?;
+        }
+    }
+    Some(result[0][0])
+}
+
+/// Accumulator-passing recursion, which the compiler may turn into a loop.
+#[wasm_bindgen]
+pub fn fibonacci_tail(n: u32) -> Option<u64> {
+    fn fib(remaining: u32, a: u64, b: u64) -> Option<u64> {
+        if remaining == 0 {
+            Some(b)
+        } else {
+            fib(remaining - 1, b, a.checked_add(b)?)
+        }
+    }
+
+    if n > FIBONACCI_MAX_N {
+        return None;
+    }
+    if n == 0 {
+        return Some(0);
+    }
+    fib(n - 1, 0, 1)
+}
+
 #[wasm_bindgen]
 pub fn eratostenes(limit: u32) -> u32 {
     let mut primes = Vec::new();
@@ -42,6 +191,136 @@ pub fn eratostenes(limit: u32) -> u32 {
     primes.len().try_into().unwrap()
 }
 
+/// Same count as `eratostenes`, with one bit per number instead of a `bool`
+/// and without collecting the primes.
+#[wasm_bindgen]
+pub fn eratostenes_bitpacked(limit: u32) -> u32 {
+    let lim = limit as usize;
+    if lim < 2 {
+        return 0;
+    }
+    // A set bit marks a composite number
+    let mut composite = vec![0u64; lim / 64 + 1];
+    // Primes above the square root have no multiples left to cross out, and
+    // squaring them would overflow usize on wasm32
+    let sqrt = lim.isqrt();
+    let mut count = 0;
+    for num in 2..=lim {
+        if composite[num / 64] & (1 << (num % 64)) == 0 {
+            count += 1;
+            if num <= sqrt {
+                let mut multiple = num * num;
+                loop {
+                    composite[multiple / 64] |= 1 << (multiple % 64);
+                    // Instead of `multiple + num > lim`, which could overflow
+                    if multiple > lim - num {
+                        break;
+                    }
+                    multiple += num;
+                }
+            }
+        }
+    }
+    count
+}
+
+// Sieves the odd numbers up to `limit`, where index i stands for 2 * i + 1,
+// and returns which of them are prime (1 is marked as not prime)
+fn sieve_odds(limit: usize) -> Vec<bool> {
+    let mut is_prime = vec![true; limit.div_ceil(2)];
+    if let Some(one) = is_prime.first_mut() {
+        *one = false;
+    }
+    // Up to the square root, as squaring larger numbers overflows usize on
+    // wasm32 and they have no multiples left to cross out
+    for num in (3..=limit.isqrt()).step_by(2) {
+        if is_prime[num / 2] {
+            // Skip the even multiples: step by 2 * num, i.e. num indices
+            let mut multiple = num * num / 2;
+            while multiple < is_prime.len() {
+                is_prime[multiple] = false;
+                multiple += num;
+            }
+        }
+    }
+    is_prime
+}
+
+/// Prime count with a sieve over odd numbers only, halving its memory.
+#[wasm_bindgen]
+pub fn eratostenes_odds(limit: u32) -> u32 {
+    if limit < 2 {
+        return 0;
+    }
+    let odd_primes = sieve_odds(limit as usize).iter().filter(|&&p| p).count();
+    // Plus 2, the only even prime
+    odd_primes as u32 + 1
+}
+
+// Fits in the L1 data cache of most CPUs
+const SIEVE_SEGMENT_SIZE: usize = 32 * 1024;
+
+/// Prime count with a segmented sieve: the primes up to sqrt(limit) cross out
+/// their multiples one cache-sized segment at a time, so memory use is
+/// independent of `limit`.
+#[wasm_bindgen]
+pub fn eratostenes_segmented(limit: u32) -> u32 {
+    if limit < 2 {
+        return 0;
+    }
+    // The segment bounds and the next multiples go past `limit`, which
+    // overflows usize on wasm32 for a limit close to u32::MAX
+    let lim = u64::from(limit);
+    let base_primes: Vec<u64> = sieve_odds(limit.isqrt() as usize)
+        .iter()
+        .enumerate()
+        .filter(|&(_, &p)| p)
+        .map(|(i, _)| 2 * i as u64 + 1)
+        .collect();
+
+    // Next multiple to cross out for each base prime, carried across segments
+    let mut next: Vec<u64> = base_primes.iter().map(|&p| p * p).collect();
+    let mut segment = vec![true; SIEVE_SEGMENT_SIZE];
+    // Start at 2 (prime) and sieve odd numbers from 3 onwards
+    let mut count = 1;
+    let mut low = 3;
+    while low <= lim {
+        let high = (low + SIEVE_SEGMENT_SIZE as u64).min(lim + 1);
+        segment.fill(true);
+        for (&prime, multiple) in base_primes.iter().zip(next.iter_mut()) {
+            while *multiple < high {
+                segment[(*multiple - low) as usize] = false;
+                // Even multiples are never counted, skip them
+                *multiple += 2 * prime;
+            }
+        }
+        count += (low..high)
+            .step_by(2)
+            .filter(|&num| segment[(num - low) as usize])
+            .count();
+        // Segments have an even size, so `low` stays odd
+        low = high;
+    }
+    count as u32
+}
+
+/// The primes up to `limit`, using the odds-only sieve.
+#[wasm_bindgen]
+pub fn eratostenes_primes(limit: u32) -> Vec<u32> {
+    if limit < 2 {
+        return Vec::new();
+    }
+    let mut primes = vec![2];
+    primes.extend(
+        sieve_odds(limit as usize)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p)
+            .map(|(i, _)| 2 * i as u32 + 1),
+    );
+    primes
+}
+
 #[wasm_bindgen]
 pub fn quicksort(arr: &mut [i32]) {
     if arr.len() <= 1 {
@@ -74,10 +353,22 @@ fn partition(arr: &mut [i32]) -> usize {
 
 #[wasm_bindgen]
 pub fn montecarlo(points: f64) -> f64 {
-    let mut rng = rand::rng();
+    montecarlo_sample(points as u64, &mut rand::rng()).estimate
+}
+
+/// Estimate of pi from the fraction of random points that fall inside the
+/// unit circle, with the standard error of that estimate.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MontecarloEstimate {
+    pub estimate: f64,
+    pub std_error: f64,
+}
+
+fn montecarlo_sample(points: u64, rng: &mut impl Rng) -> MontecarloEstimate {
     let mut inside_circle = 0;
 
-    for _ in 0..points as u64 {
+    for _ in 0..points {
         let x: f64 = rng.random();
         let y: f64 = rng.random();
 
@@ -86,7 +377,25 @@ pub fn montecarlo(points: f64) -> f64 {
         }
     }
 
-    (inside_circle as f64) / (points as f64) * 4.0
+    // Each point is a Bernoulli trial with p = pi / 4
+    let p = inside_circle as f64 / points as f64;
+    MontecarloEstimate {
+        estimate: p * 4.0,
+        std_error: 4.0 * (p * (1.0 - p) / points as f64).sqrt(),
+    }
+}
+
+/// Same as `montecarlo` with a seeded generator of the given kind, so every
+/// runtime must return exactly the same estimate for the same seed.
+#[wasm_bindgen]
+pub fn montecarlo_prng(points: f64, prng: Prng, seed: u32) -> MontecarloEstimate {
+    let (points, seed) = (points as u64, seed as u64);
+    match prng {
+        Prng::ChaCha => montecarlo_sample(points, &mut StdRng::seed_from_u64(seed)),
+        Prng::Xorshift => montecarlo_sample(points, &mut Xorshift64::new(seed)),
+        Prng::Pcg => montecarlo_sample(points, &mut Pcg32::new(seed)),
+        Prng::SplitMix => montecarlo_sample(points, &mut SplitMix64::new(seed)),
+    }
 }
 
 #[wasm_bindgen]
@@ -99,9 +408,8 @@ pub fn matrix_multiply(a: &[f64], b: &[f64], c: &mut [f64], a_width: usize) {
         "incompatible matrix dimensions: a: {}x{}, b: {}x{}",
         a_height, a_width, b_height, b_width
     );
-    assert_eq!(
+    assert!(
         c.len() >= a_height * b_width,
-        true,
         "output matrix has incorrect size: expected at least {}, got {}",
         a_height * b_width,
         c.len()
@@ -122,7 +430,6 @@ pub fn matrix_multiply(a: &[f64], b: &[f64], c: &mut [f64], a_width: usize) {
 #[wasm_bindgen]
 pub fn output_matrix_size(a_width: usize, a_len: usize) -> usize {
     let a_height = a_len / a_width;
-    let b_len = a_len;
 
     let b_width = a_height;
 
@@ -197,15 +504,207 @@ pub fn nqueens(n: u32) -> u32 {
     count
 }
 
+/// Number of solutions of the n-queens problem (OEIS A000170), indexed by n.
+pub const NQUEENS_SOLUTIONS: [u32; 19] = [
+    1, 1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200, 73712, 365596, 2279184, 14772512,
+    95815104, 666090624,
+];
+
+/// Number of solutions up to rotations and reflections (OEIS A002562),
+/// indexed by n.
+pub const NQUEENS_FUNDAMENTAL_SOLUTIONS: [u32; 19] = [
+    1, 1, 0, 0, 1, 2, 1, 6, 12, 46, 92, 341, 1787, 9233, 45752, 285053, 1846955, 11977939, 83263591,
+];
+
+// Bitboards keep one bit per column, so n is limited by the width of a u32
+const NQUEENS_MAX_N: u32 = 31;
+
+/// Same count as `nqueens`, but tracking attacked columns and diagonals as
+/// bitmasks, so every placement is O(1) instead of a scan of previous rows.
+#[wasm_bindgen]
+pub fn nqueens_bitmask(n: u32) -> u32 {
+    fn place_queen(all: u32, columns: u32, left: u32, right: u32) -> u32 {
+        if columns == all {
+            return 1;
+        }
+        let mut count = 0;
+        let mut free = all & !(columns | left | right);
+        while free != 0 {
+            // Take the lowest free column
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            count += place_queen(
+                all,
+                columns | bit,
+                ((left | bit) << 1) & all,
+                (right | bit) >> 1,
+            );
+        }
+        count
+    }
+
+    assert!(
+        n <= NQUEENS_MAX_N,
+        "n must be at most {}, got {}",
+        NQUEENS_MAX_N,
+        n
+    );
+    if n == 0 {
+        return 1;
+    }
+    place_queen(u32::MAX >> (32 - n), 0, 0, 0)
+}
+
+// Calls `visit` with every solution, as board[row] = column
+fn nqueens_enumerate(n: u32, mut visit: impl FnMut(&[u32])) {
+    fn place_queen(
+        row: usize,
+        all: u32,
+        columns: u32,
+        left: u32,
+        right: u32,
+        board: &mut [u32],
+        visit: &mut impl FnMut(&[u32]),
+    ) {
+        if row == board.len() {
+            visit(board);
+            return;
+        }
+        let mut free = all & !(columns | left | right);
+        while free != 0 {
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            board[row] = bit.trailing_zeros();
+            let (left, right) = (((left | bit) << 1) & all, (right | bit) >> 1);
+            place_queen(row + 1, all, columns | bit, left, right, board, visit);
+        }
+    }
+
+    assert!(
+        n <= NQUEENS_MAX_N,
+        "n must be at most {}, got {}",
+        NQUEENS_MAX_N,
+        n
+    );
+    let all = if n == 0 { 0 } else { u32::MAX >> (32 - n) };
+    let mut board = vec![0; n as usize];
+    place_queen(0, all, 0, 0, 0, &mut board, &mut visit);
+}
+
+/// Counts solutions up to rotations and reflections: of the (up to eight)
+/// symmetric versions of a solution, only the lexicographically smallest
+/// board is counted.
+#[wasm_bindgen]
+pub fn nqueens_fundamental(n: u32) -> u32 {
+    if n == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    let mut rotated = vec![0; n as usize];
+    let mut reflected = vec![0; n as usize];
+    nqueens_enumerate(n, |board| {
+        let last = n - 1;
+        let mut current = board.to_vec();
+        for _ in 0..4 {
+            // Rotating by 90 degrees moves (row, col) to (col, n - 1 - row)
+            for (row, &col) in current.iter().enumerate() {
+                rotated[col as usize] = last - row as u32;
+            }
+            for (row, &col) in rotated.iter().enumerate() {
+                reflected[row] = last - col;
+            }
+            if *rotated < *board || *reflected < *board {
+                return;
+            }
+            current.copy_from_slice(&rotated);
+        }
+        count += 1;
+    });
+    count
+}
+
+/// Solutions found by `nqueens_solutions`. The count is kept apart from the
+/// placements, since for n = 0 there is one solution but no placement.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NQueensSolutions {
+    count: u32,
+    placements: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl NQueensSolutions {
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Every solution concatenated, each as board[row] = column, so `n`
+    /// entries per solution.
+    pub fn placements(&self) -> Vec<u32> {
+        self.placements.clone()
+    }
+}
+
+/// Every solution for `n` queens. Meant for small boards: the placements
+/// have `n` entries per solution.
+#[wasm_bindgen]
+pub fn nqueens_solutions(n: u32) -> NQueensSolutions {
+    let mut solutions = NQueensSolutions {
+        count: 0,
+        placements: Vec::new(),
+    };
+    nqueens_enumerate(n, |board| {
+        solutions.count += 1;
+        solutions.placements.extend_from_slice(board);
+    });
+    solutions
+}
+
+// The view `mandelbrot` renders, [-2.5, 1] x [-1, 1]. Zooming divides the
+// spans and keeps the center.
+pub const MANDELBROT_CENTER_X: f64 = -0.75;
+pub const MANDELBROT_CENTER_Y: f64 = 0.0;
+const MANDELBROT_SPAN_X: f64 = 3.5;
+const MANDELBROT_SPAN_Y: f64 = 2.0;
+
 #[wasm_bindgen]
 pub fn mandelbrot(width: u32, height: u32, max_iter: u32) -> Vec<u32> {
-    let mut output = vec![0; (width * height) as usize];
+    mandelbrot_view(
+        width,
+        height,
+        max_iter,
+        MANDELBROT_CENTER_X,
+        MANDELBROT_CENTER_Y,
+        1.0,
+    )
+}
+
+/// Renders the region centered on (`center_x`, `center_y`) and `zoom` times
+/// smaller than the default view. Deep zooms keep most pixels iterating up to
+/// `max_iter`.
+#[wasm_bindgen]
+pub fn mandelbrot_view(
+    width: u32,
+    height: u32,
+    max_iter: u32,
+    center_x: f64,
+    center_y: f64,
+    zoom: f64,
+) -> Vec<u32> {
+    let mut output = vec![0; width as usize * height as usize];
+    let span_x = MANDELBROT_SPAN_X / zoom;
+    let span_y = MANDELBROT_SPAN_Y / zoom;
+    // Mapped from the top left corner, which for the default view is the
+    // same arithmetic as the original `x / width * 3.5 - 2.5`, so `mandelbrot`
+    // keeps giving the same pixels
+    let left = center_x - span_x / 2.0;
+    let top = center_y - span_y / 2.0;
 
     for y in 0..height as usize {
         for x in 0..width as usize {
             // Map pixel to complex plane
-            let cx = (x as f64 / width as f64) * 3.5 - 2.5;
-            let cy = (y as f64 / height as f64) * 2.0 - 1.0;
+            let cx = (x as f64 / width as f64) * span_x + left;
+            let cy = (y as f64 / height as f64) * span_y + top;
 
             let mut zx = 0.0;
             let mut zy = 0.0;
@@ -224,13 +723,82 @@ pub fn mandelbrot(width: u32, height: u32, max_iter: u32) -> Vec<u32> {
     output
 }
 
+/// Same as `mandelbrot_view` in single precision, which runs out of precision
+/// (and shows blocky images) at much smaller zooms.
+#[wasm_bindgen]
+pub fn mandelbrot_view_f32(
+    width: u32,
+    height: u32,
+    max_iter: u32,
+    center_x: f64,
+    center_y: f64,
+    zoom: f64,
+) -> Vec<u32> {
+    let mut output = vec![0; width as usize * height as usize];
+    let span_x = MANDELBROT_SPAN_X / zoom;
+    let span_y = MANDELBROT_SPAN_Y / zoom;
+    let left = (center_x - span_x / 2.0) as f32;
+    let top = (center_y - span_y / 2.0) as f32;
+    let (span_x, span_y) = (span_x as f32, span_y as f32);
+
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let cx = (x as f32 / width as f32) * span_x + left;
+            let cy = (y as f32 / height as f32) * span_y + top;
+
+            let mut zx = 0.0f32;
+            let mut zy = 0.0f32;
+            let mut iter_count = 0;
+
+            while (zx * zx + zy * zy) <= 4.0 && iter_count < max_iter {
+                let temp = zx * zx - zy * zy + cx;
+                zy = 2.0 * zx * zy + cy;
+                zx = temp;
+                iter_count += 1;
+            }
+            output[y * width as usize + x] = iter_count;
+        }
+    }
+    output
+}
+
 #[wasm_bindgen]
 pub fn mandelbrot_in_memory(output_ptr: *mut u32, width: u32, height: u32, max_iter: u32) {
-    let output = unsafe { slice::from_raw_parts_mut(output_ptr, (width * height) as usize) };
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, width as usize * height as usize) };
     let mandelbrot_data = mandelbrot(width, height, max_iter);
     output.copy_from_slice(&mandelbrot_data);
 }
 
+#[wasm_bindgen]
+pub fn mandelbrot_view_in_memory(
+    output_ptr: *mut u32,
+    width: u32,
+    height: u32,
+    max_iter: u32,
+    center_x: f64,
+    center_y: f64,
+    zoom: f64,
+) {
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, width as usize * height as usize) };
+    let mandelbrot_data = mandelbrot_view(width, height, max_iter, center_x, center_y, zoom);
+    output.copy_from_slice(&mandelbrot_data);
+}
+
+#[wasm_bindgen]
+pub fn mandelbrot_view_f32_in_memory(
+    output_ptr: *mut u32,
+    width: u32,
+    height: u32,
+    max_iter: u32,
+    center_x: f64,
+    center_y: f64,
+    zoom: f64,
+) {
+    let output = unsafe { slice::from_raw_parts_mut(output_ptr, width as usize * height as usize) };
+    let mandelbrot_data = mandelbrot_view_f32(width, height, max_iter, center_x, center_y, zoom);
+    output.copy_from_slice(&mandelbrot_data);
+}
+
 // ---- WASM-EXPORTED WRAPPER ----
 // This is the function we will call from JavaScript.
 #[wasm_bindgen]
@@ -273,6 +841,28 @@ pub fn allocf64(len: usize) -> *mut f64 {
     ptr
 }
 
+#[wasm_bindgen]
+pub fn allocu8(len: usize) -> *mut u8 {
+    let mut buf = Vec::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+#[wasm_bindgen]
+pub fn allocu32(len: usize) -> *mut u32 {
+    let mut buf = Vec::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+#[wasm_bindgen]
+pub fn allocf32(len: usize) -> *mut f32 {
+    let mut buf = Vec::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+
 /// Frees the memory buffer that was allocated by `alloc`.
 #[wasm_bindgen]
 pub fn dealloc(ptr: *mut i32, len: usize) {
@@ -290,6 +880,27 @@ pub fn deallocf64(ptr: *mut f64, len: usize) {
     }
 }
 
+#[wasm_bindgen]
+pub fn deallocu8(ptr: *mut u8, len: usize) {
+    unsafe {
+        let _ = Vec::from_raw_parts(ptr, 0, len);
+    }
+}
+
+#[wasm_bindgen]
+pub fn deallocu32(ptr: *mut u32, len: usize) {
+    unsafe {
+        let _ = Vec::from_raw_parts(ptr, 0, len);
+    }
+}
+
+#[wasm_bindgen]
+pub fn deallocf32(ptr: *mut f32, len: usize) {
+    unsafe {
+        let _ = Vec::from_raw_parts(ptr, 0, len);
+    }
+}
+
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 #[wasm_bindgen]
 pub fn init_panic_hook() {
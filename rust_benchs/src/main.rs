@@ -1,234 +1,503 @@
+use std::f64::consts::PI;
+use std::fs::File;
 use std::io::Write;
-use std::{fs::File, time::Instant};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
 
-use rand::Rng;
-use rand::seq::IndexedRandom;
+use rust_benchs::abstraction::{
+    abstraction_closure, abstraction_data, abstraction_dyn, abstraction_fn_pointer,
+    abstraction_generic, abstraction_index, abstraction_iterator,
+};
+use rust_benchs::bignum::{BigUint, bignum_factorial_value, bignum_pi_value, bignum_power_value};
+use rust_benchs::dynprog::{
+    dp_sequence, lcs_full, lcs_two_rows, levenshtein_full, levenshtein_two_rows,
+};
+use rust_benchs::graph::{CsrGraph, graph_bfs, graph_dijkstra};
+use rust_benchs::harness::{
+    Adaptive, Harness, Measurement, MedianInterval, ProcessVariance, median,
+};
+#[cfg(feature = "count-allocs")]
+use rust_benchs::heap::CountingAlloc;
+use rust_benchs::image::{
+    ImageFilter, image_filter_f32, image_filter_u8, image_generate, image_to_f32, life_generate,
+    life_steps,
+};
+use rust_benchs::intarith::{
+    intarith_checked, intarith_count_primes, intarith_data, intarith_div_i32, intarith_div_i64,
+};
+use rust_benchs::json::{json_document, json_typed_roundtrip, json_value_roundtrip};
+use rust_benchs::memory::{
+    memory_extend, memory_growing_calls, memory_mixed_alloc, memory_pages, memory_push,
+    memory_push_with_capacity,
+};
+use rust_benchs::perf::PerfCounts;
+use rust_benchs::prng::Prng;
+use rust_benchs::raytracer::raytrace;
+use rust_benchs::sync::{
+    sync_atomic_cas, sync_atomic_fetch_add, sync_expected, sync_mutex, sync_rwlock,
+};
+use rust_benchs::text::{
+    fnv1a, text_chars, text_corpus, text_search, text_split_words, text_to_lowercase,
+    text_utf8_validate, text_word_frequency,
+};
 use rust_benchs::{
-    eratostenes, fibonacci, mandelbrot, matrix_multiply, montecarlo, nqueens, output_matrix_size,
-    quicksort,
+    FIBONACCI_MAX_N, MANDELBROT_CENTER_X, MANDELBROT_CENTER_Y, NQUEENS_FUNDAMENTAL_SOLUTIONS,
+    NQUEENS_SOLUTIONS, eratostenes, eratostenes_bitpacked, eratostenes_odds, eratostenes_primes,
+    eratostenes_segmented, fibonacci, fibonacci_iterative, fibonacci_matrix, fibonacci_memoized,
+    fibonacci_recursive, fibonacci_tail, mandelbrot_view, mandelbrot_view_f32, matrix_multiply,
+    montecarlo, montecarlo_prng, nqueens, nqueens_bitmask, nqueens_fundamental, nqueens_solutions,
+    output_matrix_size, quicksort,
 };
 
-const WARMUP_ITERS: u32 = 100;
-const WARMUP_TIME: u32 = 30; // seconds
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
 
-pub fn benchmark<T: Copy + std::ops::AddAssign + Default + std::fmt::Display>(
-    executions: u64,
-    runtime: &str,
-    name: &str,
-    fun: fn(T) -> T,
-    arg: T,
-) {
-    let mut times = Vec::new();
-    let mut accumulator: T = Default::default();
-    let mut i = 0;
+const DEFAULT_ADAPTIVE_BUDGET: Duration = Duration::from_secs(60);
+// Set by `run_isolated` in its children, to the input file of the only test
+// they run and to their number among the processes running it
+const ISOLATED_BENCHMARK: &str = "ISOLATED_BENCHMARK";
+const ISOLATED_PROCESS: &str = "ISOLATED_PROCESS";
 
-    eprintln!("Warming up {}...", name);
-    let warmup_begin = Instant::now();
-    while i < WARMUP_ITERS && warmup_begin.elapsed().as_secs() < WARMUP_TIME as u64 {
-        let result = fun(arg);
+/// A test from the inputs, with how to measure it and where its results go.
+pub struct Benchmark<'a> {
+    pub harness: &'a Harness,
+    pub executions: u64,
+    pub runtime: &'a str,
+    pub name: &'a str,
+    /// Number of the process among those `run_isolated` runs the test in.
+    pub process: Option<u32>,
+}
 
-        accumulator += result;
-        i += 1;
+impl Benchmark<'_> {
+    // Name of the benchmark in its results files, with the number of the
+    // process in the children of `run_isolated`, so that each of them keeps
+    // its own reports
+    fn results_name(&self) -> String {
+        match self.process {
+            Some(process) => format!("{}_process{}", self.name, process),
+            None => self.name.to_string(),
+        }
     }
-    eprintln!(
-        "Warmup done in {} iterations, executing {} times...",
-        i, executions
-    );
 
-    i = 0;
-    for _ in 0..executions {
-        let start = Instant::now();
-        let result = fun(arg);
-        let elapsed = start.elapsed();
+    fn write_times(&self, measurement: &Measurement) {
+        let filename = format!(
+            "benchmark_times_{}_{}.json",
+            self.runtime,
+            self.results_name()
+        );
+        let mut file = File::create(filename).unwrap();
+        writeln!(file, "{:?}", measurement.times).unwrap();
 
-        accumulator += result;
-        times.push(elapsed.as_nanos());
-        i += 1;
-    }
+        // The times are only as good as the timer, and with batches they are
+        // averages over `batch` calls
+        let timer = &self.harness.timer;
+        self.write_report(
+            "timer",
+            serde_json::json!({
+                "resolution_ns": timer.resolution.as_nanos() as u64,
+                "overhead_ns": timer.overhead.as_nanos() as u64,
+                "batch": measurement.batch,
+            }),
+        );
 
-    let filename = format!("benchmark_times_{}_{}.json", runtime, name);
-    let mut file = File::create(filename).unwrap();
-    writeln!(file, "{:?}", times).unwrap();
+        self.write_report(
+            "warmup",
+            serde_json::json!({
+                "end": measurement.warmup_end.name(),
+                "times": measurement.warmup_times,
+            }),
+        );
 
-    eprintln!("Benchmarking of {} done.", name);
-}
+        let interval = MedianInterval::of(&measurement.times);
+        let outliers = measurement.outliers();
+        self.write_report(
+            "sampling",
+            serde_json::json!({
+                "stop_reason": measurement.stop_reason.name(),
+                "samples": measurement.times.len(),
+                "median_ns": interval.map(|interval| interval.median),
+                "ci_low_ns": interval.map(|interval| interval.low as u64),
+                "ci_high_ns": interval.map(|interval| interval.high as u64),
+                "outliers": outliers
+                    .iter()
+                    .map(|outlier| serde_json::json!({
+                        "sample": outlier.sample,
+                        "cause": outlier.cause.name(),
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+        );
+        if !outliers.is_empty() {
+            eprintln!(
+                "{} outliers: {}",
+                outliers.len(),
+                outliers
+                    .iter()
+                    .map(|outlier| format!("#{} ({})", outlier.sample, outlier.cause.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
 
-pub fn benchmark_2<T: Clone + std::ops::AddAssign + Default + std::fmt::Display>(
-    executions: u64,
-    runtime: &str,
-    name: &str,
-    fun: fn(&mut [T]),
-    arg: Vec<T>,
-) {
-    let mut times = Vec::new();
-    let mut accumulator: T = Default::default();
-    let mut i = 0;
-
-    eprintln!("Warming up {}...", name);
-    let warmup_begin = Instant::now();
-    while i < WARMUP_ITERS && warmup_begin.elapsed().as_secs() < WARMUP_TIME as u64 {
-        let mut marg = arg.clone();
-        fun(&mut marg);
-
-        for item in 0..10 {
-            accumulator += marg[item].clone();
+        if !measurement.rusage.is_empty() {
+            let rusage = &measurement.rusage;
+            self.write_report(
+                "rusage",
+                serde_json::json!({
+                    "minor_faults": column(rusage, |r| r.minor_faults),
+                    "major_faults": column(rusage, |r| r.major_faults),
+                    "voluntary_switches": column(rusage, |r| r.voluntary_switches),
+                    "involuntary_switches": column(rusage, |r| r.involuntary_switches),
+                }),
+            );
         }
-        i += 1;
-    }
-    eprintln!(
-        "Warmup done in {} iterations, executing {} times...",
-        i, executions
-    );
 
-    i = 0;
-    for _ in 0..executions {
-        let mut marg = arg.clone();
-        let start = Instant::now();
-        fun(&mut marg);
-        let elapsed = start.elapsed();
+        if cfg!(feature = "count-allocs") {
+            let heap = &measurement.heap;
+            self.write_report(
+                "heap",
+                serde_json::json!({
+                    "allocations": column(heap, |stats| stats.allocations),
+                    "bytes": column(heap, |stats| stats.bytes),
+                    "peak_live": column(heap, |stats| stats.peak_live),
+                }),
+            );
+        }
 
-        for item in 0..10 {
-            accumulator += marg[item].clone();
+        if !measurement.perf.is_empty() {
+            let perf = &measurement.perf;
+            self.write_report(
+                "perf",
+                serde_json::json!({
+                    "cycles": column(perf, |counts| counts.cycles),
+                    "instructions": column(perf, |counts| counts.instructions),
+                    "branch_misses": column(perf, |counts| counts.branch_misses),
+                    "cache_misses": column(perf, |counts| counts.cache_misses),
+                    "ipc": column(perf, PerfCounts::ipc),
+                    "branch_mpki": column(perf, PerfCounts::branch_mpki),
+                    "cache_mpki": column(perf, PerfCounts::cache_mpki),
+                }),
+            );
         }
-        times.push(elapsed.as_nanos());
-        i += 1;
+
+        eprintln!("Benchmarking of {} done.", self.name);
     }
 
-    eprintln!("Accumulator after benchmark {} is {}", name, accumulator);
+    /// Writes the size of linear memory before and after the benchmark, how
+    /// many calls grew it where the kernels count them, and how long the first
+    /// call took where it was timed apart. The growth time is how much longer
+    /// the first call took than the median of the steady-state calls in
+    /// `measurement`. Linear memory only exists on wasm, the pages are null
+    /// elsewhere.
+    fn write_memory_report(
+        &self,
+        pages_before: Option<u32>,
+        growing_calls: Option<u32>,
+        first_call: Option<(Duration, &Measurement)>,
+    ) {
+        let first_call_ns = first_call.map(|(time, _)| time.as_nanos());
+        let growth_ns = first_call
+            .map(|(time, measurement)| time.as_nanos() as f64 - median(&measurement.times));
+        self.write_report(
+            "memory",
+            serde_json::json!({
+                "growing_calls": growing_calls,
+                "pages_before": pages_before,
+                "pages_after": memory_pages(),
+                "first_call_ns": first_call_ns,
+                "growth_ns": growth_ns,
+            }),
+        );
+    }
+
+    fn write_report(&self, report: &str, values: serde_json::Value) {
+        write_report(self.runtime, &self.results_name(), report, values);
+    }
+}
 
-    let filename = format!("benchmark_times_{}_{}.json", runtime, name);
+/// Writes values measured besides the times, such as an estimate or the
+/// memory used, to `benchmark_{report}_{runtime}_{name}.json`.
+fn write_report(runtime: &str, name: &str, report: &str, values: serde_json::Value) {
+    let filename = format!("benchmark_{}_{}_{}.json", report, runtime, name);
     let mut file = File::create(filename).unwrap();
-    writeln!(file, "{:?}", times).unwrap();
+    writeln!(file, "{}", values).unwrap();
+}
 
-    eprintln!("Benchmarking of {} done.", name);
+// A field of every sample, as a JSON array
+fn column<T, V: serde::Serialize>(samples: &[T], field: impl Fn(&T) -> V) -> serde_json::Value {
+    serde_json::json!(samples.iter().map(field).collect::<Vec<_>>())
+}
+
+pub fn benchmark<T: Copy>(bench: &Benchmark, fun: fn(T) -> T, arg: T) {
+    let measurement = bench.harness.measure(
+        bench.executions,
+        bench.name,
+        &arg,
+        &mut (),
+        |&arg, _| fun(arg),
+        |_, _| (),
+    );
+    bench.write_times(&measurement);
+}
+
+/// Benchmarks a kernel that works in place, on a fresh copy of `arg` every
+/// call. The copy is restored outside of the timed region, so calls are never
+/// batched.
+pub fn benchmark_2<T: Clone>(bench: &Benchmark, fun: fn(&mut [T]), arg: Vec<T>) {
+    let mut marg = arg.clone();
+    let harness = Harness {
+        min_sample_time: Duration::ZERO,
+        ..bench.harness.clone()
+    };
+    let measurement = harness.measure(
+        bench.executions,
+        bench.name,
+        &(),
+        &mut marg,
+        |_, marg| fun(marg),
+        |marg, ()| marg.clone_from_slice(&arg),
+    );
+    bench.write_times(&measurement);
 }
 
 pub fn benchmark_4(
-    executions: u64,
-    runtime: &str,
-    name: &str,
+    bench: &Benchmark,
     fun: fn(&[f64], &[f64], &mut [f64], usize),
     a: Vec<f64>,
     b: Vec<f64>,
     width: usize,
 ) {
-    let mut times = Vec::new();
-    let mut accumulator: f64 = 0.0;
-    let mut i = 0;
-
-    let mut rng = rand::rng();
-
-    eprintln!("Warming up {}...", name);
-    let warmup_begin = Instant::now();
     let mut mc = vec![0f64; output_matrix_size(width, a.len())];
-    while i < WARMUP_ITERS && warmup_begin.elapsed().as_secs() < WARMUP_TIME as u64 {
-        fun(&a, &b, &mut mc, width);
-
-        for _ in 0..10 {
-            // Get 10 random items to avoid optimizing away the computation
-            accumulator += mc.choose(&mut rng).unwrap();
-        }
-        i += 1;
-    }
-    eprintln!(
-        "Warmup done in {} iterations, executing {} times...",
-        i, executions
+    let measurement = bench.harness.measure(
+        bench.executions,
+        bench.name,
+        &(a, b, width),
+        &mut mc,
+        |(a, b, width), mc| fun(a, b, mc, *width),
+        |_, ()| (),
     );
+    bench.write_times(&measurement);
+}
 
-    i = 0;
-    for _ in 0..executions {
-        let start = Instant::now();
-        fun(&a, &b, &mut mc, width);
-        let elapsed = start.elapsed();
-
-        for _ in 0..10 {
-            // Get 10 random items to avoid optimizing away the computation
-            accumulator += mc.choose(&mut rng).unwrap();
-        }
-        times.push(elapsed.as_nanos());
-        i += 1;
+/// Benchmarks a kernel over an input generated beforehand from the test's
+/// parameters. Every call must return the same checksum, and if the test
+/// gives an `expected` value the checksum must match it. Returns the checksum
+/// and the measurement.
+pub fn benchmark_6<I: ?Sized, O: PartialEq + std::fmt::Debug>(
+    bench: &Benchmark,
+    fun: impl Fn(&I) -> O,
+    arg: &I,
+    expected: Option<O>,
+) -> (O, Measurement) {
+    let name = bench.name;
+    let checksum = fun(arg);
+    if let Some(expected) = &expected {
+        assert_eq!(&checksum, expected, "wrong checksum for {}", name);
     }
 
-    eprintln!("Accumulator after benchmark {} is {}", name, accumulator);
-
-    let filename = format!("benchmark_times_{}_{}.json", runtime, name);
-    let mut file = File::create(filename).unwrap();
-    writeln!(file, "{:?}", times).unwrap();
+    let measurement = bench.harness.measure(
+        bench.executions,
+        bench.name,
+        arg,
+        &mut (),
+        |arg, _| fun(arg),
+        |_, result| assert_eq!(result, checksum, "checksum changed for {}", name),
+    );
 
-    eprintln!("Benchmarking of {} done.", name);
+    eprintln!("Checksum after benchmark {} is {:?}", name, checksum);
+    bench.write_times(&measurement);
+    (checksum, measurement)
 }
 
-pub fn benchmark_5(
-    executions: u64,
-    runtime: &str,
-    name: &str,
-    fun: fn(u32, u32, u32) -> Vec<u32>,
-    width: u32,
-    height: u32,
-    max_iter: u32,
+/// Benchmarks a kernel that reads an input buffer and writes its result into
+/// an output buffer allocated beforehand. The output checksum is taken after
+/// the timer stops, and must be the same for every call.
+pub fn benchmark_10<T: Copy + Default>(
+    bench: &Benchmark,
+    fun: impl Fn(&[T], &mut [T]),
+    input: &[T],
+    checksum: fn(&[T]) -> u32,
+    expected: Option<u32>,
 ) {
-    let mut times = Vec::new();
-    let mut accumulator: u32 = 0;
-    let mut i = 0;
+    let name = bench.name;
+    let mut output = vec![T::default(); input.len()];
+    fun(input, &mut output);
+    let first_checksum = checksum(&output);
+    if let Some(expected) = expected {
+        assert_eq!(first_checksum, expected, "wrong checksum for {}", name);
+    }
 
-    let mut rng = rand::rng();
+    let measurement = bench.harness.measure(
+        bench.executions,
+        bench.name,
+        input,
+        &mut output,
+        |input, output| fun(input, output),
+        |output, ()| {
+            assert_eq!(
+                checksum(output),
+                first_checksum,
+                "checksum changed for {}",
+                name
+            )
+        },
+    );
 
-    eprintln!("Warming up {}...", name);
-    let warmup_begin = Instant::now();
-    while i < WARMUP_ITERS && warmup_begin.elapsed().as_secs() < WARMUP_TIME as u64 {
-        let result = fun(width, height, max_iter);
+    eprintln!("Checksum after benchmark {} is {}", name, first_checksum);
+    bench.write_times(&measurement);
+}
 
-        for _ in 0..10 {
-            // Get 10 random items to avoid optimizing away the computation
-            let index = rng.random_range(0..result.len());
-            accumulator += result[index as usize];
-        }
-        for item in result {
-            accumulator += item;
-        }
-        i += 1;
+/// Benchmarks a kernel whose result is costly to checksum, such as a whole
+/// frame, so that only the kernel is timed. The checksum of the first result
+/// must match `expected` if the test gives one, and every call must return the
+/// same result, which is compared after the timer stops.
+pub fn benchmark_11<O: PartialEq>(
+    bench: &Benchmark,
+    fun: impl Fn() -> O,
+    checksum: impl Fn(&O) -> u32,
+    expected: Option<u32>,
+) {
+    let name = bench.name;
+    let first = fun();
+    let first_checksum = checksum(&first);
+    if let Some(expected) = expected {
+        assert_eq!(first_checksum, expected, "wrong checksum for {}", name);
     }
-    eprintln!(
-        "Warmup done in {} iterations, executing {} times...",
-        i, executions
+
+    let measurement = bench.harness.measure(
+        bench.executions,
+        bench.name,
+        &(),
+        &mut (),
+        |_, _| fun(),
+        |_, result| assert!(result == first, "result changed for {}", name),
     );
 
-    i = 0;
-    for _ in 0..executions {
-        let start = Instant::now();
-        let result = fun(width, height, max_iter);
-        let elapsed = start.elapsed();
+    eprintln!("Checksum after benchmark {} is {}", name, first_checksum);
+    bench.write_times(&measurement);
+}
 
-        for _ in 0..10 {
-            // Get 10 random items to avoid optimizing away the computation
-            let index = rng.random_range(0..result.len());
-            accumulator += result[index as usize];
-        }
-        times.push(elapsed.as_nanos());
-        i += 1;
-    }
+fn u32_checksum(values: &[u32]) -> u32 {
+    let bytes: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+    fnv1a(&bytes)
+}
 
-    eprintln!("Accumulator after benchmark {} is {}", name, accumulator);
+fn f32_checksum(values: &[f32]) -> u32 {
+    let bytes: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_bits().to_le_bytes())
+        .collect();
+    fnv1a(&bytes)
+}
+
+/// Writes `pixels` as a binary PGM image with one grey level per value, so
+/// images from different runtimes can be compared byte for byte. Values above
+/// 65535 don't fit in a PGM and are clamped.
+fn write_pgm(filename: &str, width: u32, height: u32, max_value: u32, pixels: &[u32]) {
+    let max_value = max_value.clamp(1, u16::MAX as u32);
+    let bytes: Vec<u8> = if max_value <= u8::MAX as u32 {
+        pixels.iter().map(|&p| p.min(max_value) as u8).collect()
+    } else {
+        pixels
+            .iter()
+            .flat_map(|&p| (p.min(max_value) as u16).to_be_bytes())
+            .collect()
+    };
 
-    let filename = format!("benchmark_times_{}_{}.json", runtime, name);
     let mut file = File::create(filename).unwrap();
-    writeln!(file, "{:?}", times).unwrap();
+    write!(file, "P5\n{} {}\n{}\n", width, height, max_value).unwrap();
+    file.write_all(&bytes).unwrap();
+    eprintln!("Wrote {}.", filename);
+}
 
-    eprintln!("Benchmarking of {} done.", name);
+fn expected_checksum<T: TryFrom<u64, Error: std::fmt::Debug>>(
+    test: &serde_json::Value,
+) -> Option<T> {
+    test.get("expected")
+        .map(|expected| T::try_from(expected.as_u64().unwrap()).unwrap())
 }
 
-macro_rules! benchmark {
-    ($duration:expr, $name:ident, $arg:expr) => {
-        benchmark($duration, stringify!($name), $name, $arg);
-    };
+/// Runs every test in `processes` fresh copies of this executable, one after
+/// the other, so none of them inherits the heap or the caches left by the
+/// benchmarks before it. Each child only reads and runs the input file in
+/// ISOLATED_BENCHMARK, and writes its reports with a `_process{n}` suffix on
+/// the benchmark name, so every process's times, warmup, sampling and other
+/// reports stay together. The times of all of them are then joined in the
+/// unsuffixed times file, and their spread goes to the `processes` report.
+fn run_isolated(runtime_name: &str, inputs: &[PathBuf], processes: u32) {
+    let executable = std::env::current_exe().unwrap();
+    for input in inputs {
+        let test_json = std::fs::read_to_string(input).unwrap();
+        let test = serde_json::from_str::<serde_json::Value>(&test_json).unwrap();
+        let name = test["name"].as_str().unwrap();
+
+        let mut times: Vec<Vec<u128>> = Vec::new();
+        for process in 1..=processes {
+            eprintln!("Running {} in process {}/{}...", name, process, processes);
+            let times_filename = format!(
+                "benchmark_times_{}_{}_process{}.json",
+                runtime_name, name, process
+            );
+            // A test the child skips leaves no times behind, rather than the
+            // ones of an earlier run
+            let _ = std::fs::remove_file(&times_filename);
+            let status = Command::new(&executable)
+                .arg(runtime_name)
+                .env_remove("ISOLATE")
+                .env(ISOLATED_BENCHMARK, input)
+                .env(ISOLATED_PROCESS, process.to_string())
+                .status()
+                .unwrap();
+            assert!(status.success(), "{} failed in process {}", name, process);
+            match std::fs::read_to_string(&times_filename) {
+                Ok(json) => times.push(serde_json::from_str(&json).unwrap()),
+                Err(_) => break,
+            }
+        }
+        if times.is_empty() {
+            continue;
+        }
+
+        let times_filename = format!("benchmark_times_{}_{}.json", runtime_name, name);
+        let mut file = File::create(&times_filename).unwrap();
+        writeln!(file, "{:?}", times.concat()).unwrap();
+
+        let variance = ProcessVariance::of(&times);
+        write_report(
+            runtime_name,
+            name,
+            "processes",
+            serde_json::json!({
+                "processes": times.len(),
+                "samples": times.iter().map(Vec::len).collect::<Vec<_>>(),
+                "medians_ns": times.iter().map(|times| median(times)).collect::<Vec<_>>(),
+                "between_variance": variance.map(|variance| variance.between),
+                "within_variance": variance.map(|variance| variance.within),
+            }),
+        );
+        if let Some(variance) = variance {
+            eprintln!(
+                "{} across {} processes: between-process variance {:.0} ns², within {:.0} ns².",
+                name,
+                times.len(),
+                variance.between,
+                variance.within
+            );
+        }
+    }
 }
 
-#[cfg(all(target_arch = "wasm32", target_env = "p2"))]
+#[cfg(target_os = "wasi")]
 fn get_target_string() -> &'static str {
     "wasi"
 }
 
-#[cfg(not(all(target_arch = "wasm32", target_env = "p2")))]
+#[cfg(not(target_os = "wasi"))]
 fn get_target_string() -> &'static str {
     "native"
 }
@@ -238,29 +507,108 @@ pub fn main() {
         .nth(1)
         .unwrap_or_else(|| get_target_string().to_string());
 
-    let tests_json = std::fs::read_dir("../inputs")
-        .unwrap()
-        .filter(|entry| entry.as_ref().unwrap().path().extension().unwrap() == "json")
-        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
-        .collect::<Vec<_>>();
+    // A child of `run_isolated` only reads the input of its own test
+    let isolated_benchmark = std::env::var_os(ISOLATED_BENCHMARK).map(PathBuf::from);
+    let process = std::env::var(ISOLATED_PROCESS)
+        .ok()
+        .map(|process| process.parse().unwrap());
+    let inputs = match isolated_benchmark {
+        Some(input) => vec![input],
+        None => std::fs::read_dir("../inputs")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().unwrap() == "json")
+            .collect::<Vec<_>>(),
+    };
 
+    // Set ISOLATE to run every benchmark in a process of its own, or ISOLATE=n
+    // to run it in n processes and compare their times. wasi can't spawn
+    // processes, so there it is ignored.
+    if let Ok(processes) = std::env::var("ISOLATE") {
+        let processes = if processes.is_empty() {
+            1
+        } else {
+            processes.parse().unwrap()
+        };
+        if cfg!(target_os = "wasi") {
+            eprintln!("Can't spawn processes on this target, running every benchmark here.");
+        } else {
+            run_isolated(&runtime_name, &inputs, processes);
+            return;
+        }
+    }
+
+    let tests_json = inputs
+        .iter()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect::<Vec<_>>();
     println!("Loaded {} tests.", tests_json.len());
 
+    // Set DUMP_IMAGES to also write the images computed by the image-producing
+    // benchmarks, to check that every runtime produced the same picture
+    let dump_images = std::env::var_os("DUMP_IMAGES").is_some();
+    let mut harness = Harness {
+        // Set PERF_COUNTERS to also read the CPU's cycles, instructions,
+        // branch misses and cache misses around every sample, on Linux
+        perf_counters: std::env::var_os("PERF_COUNTERS").is_some(),
+        ..Harness::default()
+    };
+    // Set ADAPTIVE_TARGET, e.g. to 0.01, to sample every benchmark until the
+    // 95% confidence interval of its median is within ±1%, or until
+    // ADAPTIVE_BUDGET seconds (60 by default) run out, ignoring `executions`
+    if let Ok(target) = std::env::var("ADAPTIVE_TARGET") {
+        let budget = std::env::var("ADAPTIVE_BUDGET").map_or(DEFAULT_ADAPTIVE_BUDGET, |budget| {
+            Duration::from_secs_f64(budget.parse().unwrap())
+        });
+        harness.adaptive = Some(Adaptive {
+            target: target.parse().unwrap(),
+            budget,
+        });
+    }
+    eprintln!(
+        "Timer resolution {:?}, overhead {:?}: batching calls shorter than {:?}.",
+        harness.timer.resolution, harness.timer.overhead, harness.min_sample_time
+    );
+
     for test_json in tests_json {
         let test = serde_json::from_str::<serde_json::Value>(&test_json).unwrap();
         let typ = test["type"].as_u64().unwrap();
         let executions = test["executions"].as_u64().unwrap();
         let name = test["name"].as_str().unwrap();
+        let bench = Benchmark {
+            harness: &harness,
+            executions,
+            runtime: &runtime_name,
+            name,
+            process,
+        };
         match typ {
             1 => {
                 let fun: fn(u32) -> u32 = match test["fun"].as_str() {
                     Some("fibonacci") => fibonacci,
                     Some("eratostenes") => eratostenes,
+                    Some("eratostenes_bitpacked") => eratostenes_bitpacked,
+                    Some("eratostenes_odds") => eratostenes_odds,
+                    Some("eratostenes_segmented") => eratostenes_segmented,
+                    Some("eratostenes_primes") => |limit| eratostenes_primes(limit).len() as u32,
                     Some("nqueens") => nqueens,
                     _ => continue,
                 };
-                let arg = test["arg"].as_u64().unwrap();
-                benchmark(executions, &runtime_name, name, fun, arg as u32);
+                let arg = test["arg"].as_u64().unwrap() as u32;
+                let expected = expected_checksum(&test);
+                let pages_before = memory_pages();
+                benchmark_6(&bench, |&arg: &u32| fun(arg), &arg, expected);
+
+                // The sieve variants differ mostly in the memory they need,
+                // which on wasm shows as linear memory growth. Memory grown
+                // by earlier benchmarks is reused, so the growth is only
+                // comparable between runs with ISOLATE set
+                if test["fun"]
+                    .as_str()
+                    .is_some_and(|fun| fun.starts_with("eratostenes"))
+                {
+                    bench.write_memory_report(pages_before, None, None);
+                }
             }
             2 => {
                 let fun = match test["fun"].as_str() {
@@ -268,7 +616,7 @@ pub fn main() {
                     _ => continue,
                 };
                 let arg: Vec<i32> = serde_json::from_value(test["arg"].clone()).unwrap();
-                benchmark_2(executions, &runtime_name, name, fun, arg);
+                benchmark_2(&bench, fun, arg);
             }
             3 => {
                 let fun: fn(f64) -> f64 = match test["fun"].as_str() {
@@ -276,7 +624,19 @@ pub fn main() {
                     _ => continue,
                 };
                 let arg = test["arg"].as_f64().unwrap();
-                benchmark(executions, &runtime_name, name, fun, arg);
+                // Unseeded, so the estimate can only be checked against pi:
+                // within 6 standard errors, which fails by chance about once
+                // in 500 million runs
+                let p = PI / 4.0;
+                let std_error = 4.0 * (p * (1.0 - p) / arg).sqrt();
+                let estimate = fun(arg);
+                assert!(
+                    (estimate - PI).abs() <= 6.0 * std_error,
+                    "estimate {} of {} is too far from pi",
+                    estimate,
+                    name
+                );
+                benchmark(&bench, fun, arg);
             }
             4 => {
                 let fun = match test["fun"].as_str() {
@@ -286,24 +646,416 @@ pub fn main() {
                 let a = serde_json::from_value::<Vec<f64>>(test["arg"]["a"].clone()).unwrap();
                 let b = serde_json::from_value::<Vec<f64>>(test["arg"]["b"].clone()).unwrap();
                 let width = test["arg"]["width"].as_u64().unwrap() as usize;
-                benchmark_4(executions, &runtime_name, name, fun, a, b, width);
+                benchmark_4(&bench, fun, a, b, width);
             }
             5 => {
-                let fun = match test["fun"].as_str() {
-                    Some("mandelbrot") => mandelbrot,
+                let fun: fn(u32, u32, u32, f64, f64, f64) -> Vec<u32> = match test["fun"].as_str() {
+                    Some("mandelbrot") => mandelbrot_view,
+                    Some("mandelbrot_f32") => mandelbrot_view_f32,
                     _ => continue,
                 };
                 let width = test["arg"]["width"].as_u64().unwrap() as u32;
                 let height = test["arg"]["height"].as_u64().unwrap() as u32;
                 let max_iter = test["arg"]["max_iter"].as_u64().unwrap() as u32;
-                benchmark_5(
-                    executions,
-                    &runtime_name,
-                    name,
-                    fun,
-                    width,
-                    height,
-                    max_iter,
+                let center_x = test["arg"]["center_x"]
+                    .as_f64()
+                    .unwrap_or(MANDELBROT_CENTER_X);
+                let center_y = test["arg"]["center_y"]
+                    .as_f64()
+                    .unwrap_or(MANDELBROT_CENTER_Y);
+                let zoom = test["arg"]["zoom"].as_f64().unwrap_or(1.0);
+                let view = |width, height, max_iter| {
+                    fun(width, height, max_iter, center_x, center_y, zoom)
+                };
+                let expected = expected_checksum(&test);
+                let fun = || view(width, height, max_iter);
+                benchmark_11(&bench, fun, |pixels| u32_checksum(pixels), expected);
+                if dump_images {
+                    let filename = format!("image_{}_{}.pgm", runtime_name, name);
+                    let pixels = view(width, height, max_iter);
+                    write_pgm(&filename, width, height, max_iter, &pixels);
+                }
+            }
+            6 => {
+                let arg = &test["arg"];
+                let corpus = text_corpus(
+                    arg["len"].as_u64().unwrap() as usize,
+                    arg["non_ascii_ratio"].as_f64().unwrap(),
+                    arg["seed"].as_u64().unwrap() as u32,
+                );
+                let expected = expected_checksum(&test);
+                let fun: fn(&str) -> u32 = match test["fun"].as_str() {
+                    Some("text_utf8_validate") => {
+                        let bytes = corpus.as_bytes();
+                        let fun = text_utf8_validate;
+                        benchmark_6(&bench, fun, bytes, expected);
+                        continue;
+                    }
+                    Some("text_search") => {
+                        let needle = arg["needle"].as_str().unwrap();
+                        let fun = |text: &str| text_search(text, needle);
+                        benchmark_6(&bench, fun, &corpus, expected);
+                        continue;
+                    }
+                    Some("text_chars") => text_chars,
+                    Some("text_to_lowercase") => text_to_lowercase,
+                    Some("text_split_words") => text_split_words,
+                    Some("text_word_frequency") => text_word_frequency,
+                    _ => continue,
+                };
+                benchmark_6(&bench, fun, &corpus, expected);
+            }
+            7 => {
+                let fun: fn(&str) -> u32 = match test["fun"].as_str() {
+                    Some("json_value_roundtrip") => json_value_roundtrip,
+                    Some("json_typed_roundtrip") => json_typed_roundtrip,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let document = json_document(
+                    arg["records"].as_u64().unwrap() as u32,
+                    arg["shape"].as_str().unwrap(),
+                    arg["seed"].as_u64().unwrap() as u32,
+                );
+                let expected = expected_checksum(&test);
+                benchmark_6(&bench, fun, &document, expected);
+            }
+            8 => {
+                let fun: fn(&[u8], &[u8]) -> u32 = match test["fun"].as_str() {
+                    Some("levenshtein_full") => levenshtein_full,
+                    Some("levenshtein_two_rows") => levenshtein_two_rows,
+                    Some("lcs_full") => lcs_full,
+                    Some("lcs_two_rows") => lcs_two_rows,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let alphabet = arg["alphabet"].as_u64().unwrap() as u8;
+                let seed = arg["seed"].as_u64().unwrap() as u32;
+                let a = dp_sequence(arg["len_a"].as_u64().unwrap() as usize, alphabet, seed);
+                let b = dp_sequence(arg["len_b"].as_u64().unwrap() as usize, alphabet, seed + 1);
+                let expected = expected_checksum(&test);
+                let fun = |(a, b): &(Vec<u8>, Vec<u8>)| fun(a, b);
+                benchmark_6(&bench, fun, &(a, b), expected);
+            }
+            9 => {
+                let fun: fn(&CsrGraph, u32) -> u32 = match test["fun"].as_str() {
+                    Some("graph_bfs") => graph_bfs,
+                    Some("graph_dijkstra") => graph_dijkstra,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let seed = arg["seed"].as_u64().unwrap() as u32;
+                let graph = match arg["kind"].as_str() {
+                    Some("random") => {
+                        let vertices = arg["vertices"].as_u64().unwrap() as u32;
+                        if vertices == 0 {
+                            eprintln!("Skipping {}: a random graph needs vertices.", name);
+                            continue;
+                        }
+                        CsrGraph::random(vertices, arg["edges"].as_u64().unwrap() as u32, seed)
+                    }
+                    Some("grid") => CsrGraph::grid(
+                        arg["width"].as_u64().unwrap() as u32,
+                        arg["height"].as_u64().unwrap() as u32,
+                        seed,
+                    ),
+                    _ => continue,
+                };
+                let source = arg["source"].as_u64().unwrap() as u32;
+                if source >= graph.vertices() {
+                    eprintln!(
+                        "Skipping {}: source {} is not in a graph of {} vertices.",
+                        name,
+                        source,
+                        graph.vertices()
+                    );
+                    continue;
+                }
+                let expected = expected_checksum(&test);
+                let fun = |graph: &CsrGraph| fun(graph, source);
+                benchmark_6(&bench, fun, &graph, expected);
+            }
+            10 => {
+                let arg = &test["arg"];
+                let width = arg["width"].as_u64().unwrap() as u32;
+                let height = arg["height"].as_u64().unwrap() as u32;
+                let seed = arg["seed"].as_u64().unwrap() as u32;
+                let expected = expected_checksum(&test);
+                match test["fun"].as_str() {
+                    Some("image_filter_u8") => {
+                        let filter =
+                            ImageFilter::from_name(arg["filter"].as_str().unwrap()).unwrap();
+                        let image = image_generate(width, height, seed);
+                        let fun = |input: &[u8], output: &mut [u8]| {
+                            image_filter_u8(input, output, width, height, filter)
+                        };
+                        benchmark_10(&bench, fun, &image, fnv1a, expected);
+                    }
+                    Some("image_filter_f32") => {
+                        let filter =
+                            ImageFilter::from_name(arg["filter"].as_str().unwrap()).unwrap();
+                        let image = image_to_f32(&image_generate(width, height, seed));
+                        let fun = |input: &[f32], output: &mut [f32]| {
+                            image_filter_f32(input, output, width, height, filter)
+                        };
+                        let checksum = f32_checksum;
+                        benchmark_10(&bench, fun, &image, checksum, expected);
+                    }
+                    Some("life_steps") => {
+                        let steps = arg["steps"].as_u64().unwrap() as u32;
+                        let board = life_generate(width, height, seed);
+                        let fun = |input: &[u8], output: &mut [u8]| {
+                            life_steps(input, output, width, height, steps)
+                        };
+                        benchmark_10(&bench, fun, &board, fnv1a, expected);
+                    }
+                    _ => continue,
+                }
+            }
+            11 => {
+                let fun = match test["fun"].as_str() {
+                    Some("raytrace") => raytrace,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let width = arg["width"].as_u64().unwrap() as u32;
+                let height = arg["height"].as_u64().unwrap() as u32;
+                let samples = arg["samples"].as_u64().unwrap() as u32;
+                let expected = expected_checksum(&test);
+                let fun = || fun(width, height, samples);
+                benchmark_11(&bench, fun, |frame| fnv1a(frame), expected);
+            }
+            12 => {
+                let arg = &test["arg"];
+                let expected = expected_checksum(&test);
+                // Only the limb arithmetic is timed, the decimal digits are
+                // worked out and checked after the timer stops
+                let pi_digits = match test["fun"].as_str() {
+                    Some("bignum_pi") => Some(arg["digits"].as_u64().unwrap() as u32),
+                    _ => None,
+                };
+                let fun: Box<dyn Fn() -> BigUint> = match test["fun"].as_str() {
+                    Some("bignum_factorial") => {
+                        let n = arg["n"].as_u64().unwrap() as u32;
+                        Box::new(move || bignum_factorial_value(n))
+                    }
+                    Some("bignum_power") => {
+                        let base = arg["base"].as_u64().unwrap();
+                        let exponent = arg["exponent"].as_u64().unwrap() as u32;
+                        Box::new(move || bignum_power_value(base, exponent))
+                    }
+                    Some("bignum_pi") => {
+                        let digits = pi_digits.unwrap();
+                        Box::new(move || bignum_pi_value(digits))
+                    }
+                    _ => continue,
+                };
+                // Pi is in fixed point, and printed to the digits asked for
+                let decimal = |value: &BigUint| match pi_digits {
+                    Some(digits) => value.pi_decimal(digits),
+                    None => value.to_decimal(),
+                };
+                let checksum = |value: &BigUint| fnv1a(decimal(value).as_bytes());
+                benchmark_11(&bench, fun, checksum, expected);
+            }
+            13 => {
+                let fun: fn(u32) -> Option<u64> = match test["fun"].as_str() {
+                    Some("fibonacci_recursive") => fibonacci_recursive,
+                    Some("fibonacci_iterative") => fibonacci_iterative,
+                    Some("fibonacci_memoized") => fibonacci_memoized,
+                    Some("fibonacci_matrix") => fibonacci_matrix,
+                    Some("fibonacci_tail") => fibonacci_tail,
+                    _ => continue,
+                };
+                let arg = test["arg"].as_u64().unwrap() as u32;
+                if arg > FIBONACCI_MAX_N {
+                    eprintln!(
+                        "Skipping {}: fibonacci({}) does not fit in a u64 (max n is {}).",
+                        name, arg, FIBONACCI_MAX_N
+                    );
+                    continue;
+                }
+                let expected = expected_checksum(&test);
+                let fun = |&n: &u32| fun(n).unwrap();
+                benchmark_6(&bench, fun, &arg, expected);
+            }
+            14 => {
+                // Counts are checked against the OEIS sequences rather than
+                // an expected value in the test
+                let (fun, oeis): (fn(u32) -> u32, &[u32]) = match test["fun"].as_str() {
+                    Some("nqueens") => (nqueens, &NQUEENS_SOLUTIONS),
+                    Some("nqueens_bitmask") => (nqueens_bitmask, &NQUEENS_SOLUTIONS),
+                    Some("nqueens_fundamental") => {
+                        (nqueens_fundamental, &NQUEENS_FUNDAMENTAL_SOLUTIONS)
+                    }
+                    Some("nqueens_solutions") => {
+                        (|n| nqueens_solutions(n).count(), &NQUEENS_SOLUTIONS)
+                    }
+                    _ => continue,
+                };
+                let arg = test["arg"].as_u64().unwrap() as u32;
+                let expected = oeis.get(arg as usize).copied();
+                // The solutions come in a fixed order, so their placements
+                // can be checked as well as their count
+                if test["fun"] == "nqueens_solutions"
+                    && let Some(expected) = expected_checksum::<u32>(&test)
+                {
+                    let placements = nqueens_solutions(arg).placements();
+                    assert_eq!(
+                        u32_checksum(&placements),
+                        expected,
+                        "wrong placements for {}",
+                        name
+                    );
+                }
+                let fun = |&n: &u32| fun(n);
+                benchmark_6(&bench, fun, &arg, expected);
+            }
+            15 => {
+                let fun = match test["fun"].as_str() {
+                    Some("montecarlo_prng") => montecarlo_prng,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let points = arg["points"].as_f64().unwrap();
+                let prng = Prng::from_name(arg["prng"].as_str().unwrap()).unwrap();
+                let seed = arg["seed"].as_u64().unwrap() as u32;
+                let fun = |_: &()| fun(points, prng, seed);
+                let (result, _) = benchmark_6(&bench, fun, &(), None);
+                // Seeded, so every runtime must give exactly this estimate
+                if let Some(expected) = test["expected"].as_f64() {
+                    assert_eq!(result.estimate, expected, "wrong estimate for {}", name);
+                }
+
+                // The estimate is the same on every run, so it is written once
+                // next to the times
+                bench.write_report(
+                    "estimate",
+                    serde_json::json!({
+                        "estimate": result.estimate,
+                        "std_error": result.std_error,
+                    }),
+                );
+            }
+            16 => {
+                // Kernels come in pairs doing the same work, which must give
+                // the same checksum
+                let fun: fn(&[u32]) -> u32 = match test["fun"].as_str() {
+                    Some("abstraction_iterator") => abstraction_iterator,
+                    Some("abstraction_index") => abstraction_index,
+                    Some("abstraction_generic") => abstraction_generic,
+                    Some("abstraction_dyn") => abstraction_dyn,
+                    Some("abstraction_closure") => abstraction_closure,
+                    Some("abstraction_fn_pointer") => abstraction_fn_pointer,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let data = abstraction_data(
+                    arg["len"].as_u64().unwrap() as usize,
+                    arg["seed"].as_u64().unwrap() as u32,
+                );
+                let expected = expected_checksum(&test);
+                benchmark_6(&bench, fun, &data[..], expected);
+            }
+            17 => {
+                let fun: fn(&[u32]) -> u32 = match test["fun"].as_str() {
+                    Some("intarith_div_i32") => intarith_div_i32,
+                    Some("intarith_div_i64") => intarith_div_i64,
+                    Some("intarith_checked") => intarith_checked,
+                    Some("intarith_count_primes") => intarith_count_primes,
+                    _ => continue,
+                };
+                let arg = &test["arg"];
+                let data = intarith_data(
+                    arg["len"].as_u64().unwrap() as usize,
+                    arg["seed"].as_u64().unwrap() as u32,
+                );
+                let expected = expected_checksum(&test);
+                benchmark_6(&bench, fun, &data[..], expected);
+            }
+            18 => {
+                let arg = &test["arg"];
+                let threads = arg["threads"].as_u64().unwrap() as u32;
+                let ops = arg["ops"].as_u64().unwrap() as u32;
+                let updates = threads as u64 * ops as u64;
+                // Results are checked against a value derived from the number
+                // of updates, which the test's expected value, where it has
+                // one, must match
+                let (fun, expected): (Box<dyn Fn(u32) -> Option<u64>>, u64) =
+                    match test["fun"].as_str() {
+                        Some("sync_atomic_fetch_add") => {
+                            (Box::new(|ops| sync_atomic_fetch_add(threads, ops)), updates)
+                        }
+                        Some("sync_atomic_cas") => (
+                            Box::new(|ops| sync_atomic_cas(threads, ops)),
+                            sync_expected(updates),
+                        ),
+                        Some("sync_mutex") => (
+                            Box::new(|ops| sync_mutex(threads, ops)),
+                            sync_expected(updates),
+                        ),
+                        Some("sync_rwlock") => {
+                            let write_every = arg["write_every"].as_u64().unwrap() as u32;
+                            let writes = threads as u64 * ops.div_ceil(write_every) as u64;
+                            (
+                                Box::new(move |ops| sync_rwlock(threads, ops, write_every)),
+                                sync_expected(writes),
+                            )
+                        }
+                        _ => continue,
+                    };
+                if let Some(from_test) = expected_checksum::<u64>(&test) {
+                    assert_eq!(expected, from_test, "wrong expected value for {}", name);
+                }
+                if fun(1).is_none() {
+                    eprintln!(
+                        "Skipping {}: {} threads can't be spawned on this target.",
+                        name, threads
+                    );
+                    continue;
+                }
+                let fun = |&ops: &u32| fun(ops).unwrap();
+                benchmark_6(&bench, fun, &ops, Some(expected));
+            }
+            19 => {
+                let arg = &test["arg"];
+                let fun: Box<dyn Fn() -> u32> = match test["fun"].as_str() {
+                    Some("memory_push") => {
+                        let count = arg["count"].as_u64().unwrap() as u32;
+                        Box::new(move || memory_push(count))
+                    }
+                    Some("memory_push_with_capacity") => {
+                        let count = arg["count"].as_u64().unwrap() as u32;
+                        Box::new(move || memory_push_with_capacity(count))
+                    }
+                    Some("memory_extend") => {
+                        let count = arg["count"].as_u64().unwrap() as u32;
+                        let chunk = arg["chunk"].as_u64().unwrap() as u32;
+                        Box::new(move || memory_extend(count, chunk))
+                    }
+                    Some("memory_mixed_alloc") => {
+                        let rounds = arg["rounds"].as_u64().unwrap() as u32;
+                        let seed = arg["seed"].as_u64().unwrap() as u32;
+                        Box::new(move || memory_mixed_alloc(rounds, seed))
+                    }
+                    _ => continue,
+                };
+                let expected = expected_checksum(&test);
+                let growing_calls_before = memory_growing_calls();
+                let pages_before = memory_pages();
+                // The first call is usually the only one that grows linear
+                // memory, and the warmup keeps it out of the times, so the
+                // cost of growing is timed on its own here
+                let start = Instant::now();
+                std::hint::black_box(fun());
+                let first_call = start.elapsed();
+                let fun = |_: &()| fun();
+                let (_, measurement) = benchmark_6(&bench, fun, &(), expected);
+
+                bench.write_memory_report(
+                    pages_before,
+                    Some(memory_growing_calls() - growing_calls_before),
+                    Some((first_call, &measurement)),
                 );
             }
             _ => continue,
@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+// On wasm the allocator gets more memory with `memory.grow`, which some
+// engines implement by copying or remapping the whole linear memory. The
+// kernels compare the memory size before and after their work, outside of
+// their loops, and count the calls that grew it, however many `memory.grow`
+// steps each call took; freed memory is kept by the allocator, so usually
+// only the first call of a kernel grows it.
+
+static GROWING_CALLS: AtomicU32 = AtomicU32::new(0);
+
+const MIXED_LIVE_BUFFERS: usize = 64;
+const MIXED_MAX_SIZE_LOG2: u32 = 20;
+
+/// Current size of linear memory in 64 KiB pages, or `None` when not running
+/// on wasm.
+#[wasm_bindgen]
+pub fn memory_pages() -> Option<u32> {
+    #[cfg(target_arch = "wasm32")]
+    return Some(core::arch::wasm32::memory_size::<0>() as u32);
+    #[cfg(not(target_arch = "wasm32"))]
+    None
+}
+
+/// Calls of the memory kernels that grew linear memory, since the program
+/// started. A call counts once, whether it grew memory in one step or many.
+#[wasm_bindgen]
+pub fn memory_growing_calls() -> u32 {
+    GROWING_CALLS.load(Ordering::Relaxed)
+}
+
+// Called at the end of a kernel, with the memory size seen when it started
+fn track_growth(pages_before: Option<u32>) {
+    if memory_pages() > pages_before {
+        GROWING_CALLS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn value_at(i: u32) -> u32 {
+    i.wrapping_mul(2654435761)
+}
+
+fn checksum(values: &[u32]) -> u32 {
+    values.iter().fold(values.len() as u32, |hash, &value| {
+        hash.rotate_left(5) ^ value
+    })
+}
+
+/// Pushes `count` values one by one into a `Vec` that starts empty, so it
+/// reallocates every time it doubles.
+#[wasm_bindgen]
+pub fn memory_push(count: u32) -> u32 {
+    let pages = memory_pages();
+    let mut values = Vec::new();
+    for i in 0..count {
+        values.push(value_at(i));
+    }
+    track_growth(pages);
+    checksum(&values)
+}
+
+/// Same as `memory_push` into a `Vec` created `with_capacity(count)`.
+#[wasm_bindgen]
+pub fn memory_push_with_capacity(count: u32) -> u32 {
+    let pages = memory_pages();
+    let mut values = Vec::with_capacity(count as usize);
+    track_growth(pages);
+    for i in 0..count {
+        values.push(value_at(i));
+    }
+    checksum(&values)
+}
+
+/// Builds the same `Vec` as `memory_push` with `extend`, `chunk` values at a
+/// time. `chunk` must be at least 1.
+#[wasm_bindgen]
+pub fn memory_extend(count: u32, chunk: u32) -> u32 {
+    assert!(chunk > 0, "chunk must be at least 1, got 0");
+    let pages = memory_pages();
+    let mut values = Vec::new();
+    for start in (0..count).step_by(chunk as usize) {
+        values.extend((start..count.min(start.saturating_add(chunk))).map(value_at));
+    }
+    track_growth(pages);
+    checksum(&values)
+}
+
+/// Allocates `rounds` buffers of random sizes between 1 byte and 1 MiB
+/// (uniform on a log scale), each replacing, and so freeing, one of 64 live
+/// buffers chosen at random.
+#[wasm_bindgen]
+pub fn memory_mixed_alloc(rounds: u32, seed: u32) -> u32 {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let pages = memory_pages();
+    let mut buffers: Vec<Vec<u8>> = vec![Vec::new(); MIXED_LIVE_BUFFERS];
+    let mut hash: u32 = 0;
+    for round in 0..rounds {
+        let size = 1 << rng.random_range(0..=MIXED_MAX_SIZE_LOG2);
+        let size = rng.random_range(size / 2 + 1..=size);
+        let slot = rng.random_range(0..MIXED_LIVE_BUFFERS);
+
+        // Fill the buffer so its pages are really touched
+        buffers[slot] = vec![round as u8; size];
+        let buffer = &buffers[slot];
+        hash = hash.rotate_left(5) ^ buffer.len() as u32 ^ buffer[size - 1] as u32;
+    }
+    track_growth(pages);
+    hash
+}
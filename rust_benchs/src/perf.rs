@@ -0,0 +1,253 @@
+// Hardware performance counters around the measured calls, read with Linux's
+// `perf_event_open`. Only the calling thread is counted, and only in user
+// space, which `perf_event_paranoid` up to 2 allows. Counters the CPU, the
+// kernel or a virtual machine don't provide are left out, and on other
+// systems, wasi included, none are.
+
+/// Counts over one call of a kernel, `None` for the counters unavailable.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PerfCounts {
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub branch_misses: Option<u64>,
+    pub cache_misses: Option<u64>,
+}
+
+impl PerfCounts {
+    /// Instructions per cycle.
+    pub fn ipc(&self) -> Option<f64> {
+        Some(self.instructions? as f64 / self.cycles? as f64)
+    }
+
+    /// Branch misses per thousand instructions.
+    pub fn branch_mpki(&self) -> Option<f64> {
+        Some(self.branch_misses? as f64 * 1000.0 / self.instructions? as f64)
+    }
+
+    /// Last level cache misses per thousand instructions.
+    pub fn cache_mpki(&self) -> Option<f64> {
+        Some(self.cache_misses? as f64 * 1000.0 / self.instructions? as f64)
+    }
+}
+
+/// A group of counters, enabled and disabled together so their ratios are
+/// consistent.
+#[derive(Debug)]
+pub struct PerfCounters {
+    group: sys::Group,
+}
+
+impl PerfCounters {
+    /// Opens every counter the system provides, or returns `None` if it
+    /// provides none.
+    pub fn open() -> Option<PerfCounters> {
+        sys::Group::open().map(|group| PerfCounters { group })
+    }
+
+    /// Resets the counters and starts counting.
+    pub fn start(&self) {
+        self.group.start();
+    }
+
+    /// Stops counting and returns the counts since `start`, divided by
+    /// `calls`. Counts are scaled up if the kernel had to multiplex the
+    /// counters, and are all `None` if they never got to run.
+    pub fn stop(&self, calls: u64) -> PerfCounts {
+        self.group.stop(calls)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use super::PerfCounts;
+
+    #[derive(Debug)]
+    pub enum Group {}
+
+    impl Group {
+        pub fn open() -> Option<Group> {
+            None
+        }
+
+        pub fn start(&self) {
+            match *self {}
+        }
+
+        pub fn stop(&self, _calls: u64) -> PerfCounts {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+    use super::PerfCounts;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Event {
+        Cycles,
+        Instructions,
+        BranchMisses,
+        CacheMisses,
+    }
+
+    const EVENTS: [Event; 4] = [
+        Event::Cycles,
+        Event::Instructions,
+        Event::BranchMisses,
+        Event::CacheMisses,
+    ];
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+
+    const FLAG_DISABLED: u64 = 1 << 0;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    const PERF_EVENT_IOC_ENABLE: libc::Ioctl = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::Ioctl = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::Ioctl = 0x2403;
+    const PERF_IOC_FLAG_GROUP: libc::c_ulong = 1;
+
+    // `struct perf_event_attr` up to PERF_ATTR_SIZE_VER5, which libc doesn't
+    // define. The bit fields are all in `flags`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+        config2: u64,
+        branch_sample_type: u64,
+        sample_regs_user: u64,
+        sample_stack_user: u32,
+        clockid: i32,
+        sample_regs_intr: u64,
+        aux_watermark: u32,
+        sample_max_stack: u16,
+        reserved: u16,
+    }
+
+    const _: () = assert!(size_of::<PerfEventAttr>() == 112);
+
+    // Opens a counter for `event` on the calling thread, as the leader of a
+    // new group, which starts disabled, or as a member of `leader`'s group
+    fn open_event(event: Event, leader: Option<RawFd>) -> Option<OwnedFd> {
+        let config = match event {
+            Event::Cycles => PERF_COUNT_HW_CPU_CYCLES,
+            Event::Instructions => PERF_COUNT_HW_INSTRUCTIONS,
+            Event::BranchMisses => PERF_COUNT_HW_BRANCH_MISSES,
+            Event::CacheMisses => PERF_COUNT_HW_CACHE_MISSES,
+        };
+        let mut flags = FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV;
+        if leader.is_none() {
+            flags |= FLAG_DISABLED;
+        }
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP
+                | PERF_FORMAT_TOTAL_TIME_ENABLED
+                | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            flags,
+            ..Default::default()
+        };
+        // SAFETY: `attr` is a valid `perf_event_attr` of the size it says,
+        // and a successful call returns a new file descriptor we own
+        unsafe {
+            let fd = libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                leader.unwrap_or(-1),
+                PERF_FLAG_FD_CLOEXEC,
+            );
+            (fd >= 0).then(|| OwnedFd::from_raw_fd(fd as RawFd))
+        }
+    }
+
+    /// The counters that could be opened, the first one leading the group.
+    #[derive(Debug)]
+    pub struct Group {
+        events: Vec<(Event, OwnedFd)>,
+    }
+
+    impl Group {
+        pub fn open() -> Option<Group> {
+            let mut events: Vec<(Event, OwnedFd)> = Vec::new();
+            for event in EVENTS {
+                let leader = events.first().map(|(_, fd)| fd.as_raw_fd());
+                if let Some(fd) = open_event(event, leader) {
+                    events.push((event, fd));
+                }
+            }
+            (!events.is_empty()).then_some(Group { events })
+        }
+
+        fn leader(&self) -> RawFd {
+            self.events[0].1.as_raw_fd()
+        }
+
+        fn ioctl(&self, request: libc::Ioctl) {
+            // SAFETY: the descriptor is a perf event we own, and these
+            // requests take an integer argument
+            unsafe { libc::ioctl(self.leader(), request, PERF_IOC_FLAG_GROUP) };
+        }
+
+        pub fn start(&self) {
+            self.ioctl(PERF_EVENT_IOC_RESET);
+            self.ioctl(PERF_EVENT_IOC_ENABLE);
+        }
+
+        pub fn stop(&self, calls: u64) -> PerfCounts {
+            self.ioctl(PERF_EVENT_IOC_DISABLE);
+
+            // Number of counters, time enabled, time running, then the counts
+            let mut values = [0u64; 3 + EVENTS.len()];
+            // SAFETY: `values` is a writable buffer of the size passed
+            let read = unsafe {
+                libc::read(
+                    self.leader(),
+                    values.as_mut_ptr().cast(),
+                    size_of_val(&values),
+                )
+            };
+            let mut counts = PerfCounts::default();
+            let (enabled, running) = (values[1], values[2]);
+            if read <= 0 || running == 0 {
+                return counts;
+            }
+            let scale = enabled as f64 / running as f64;
+            for (&(event, _), &value) in self.events.iter().zip(&values[3..]) {
+                let value = Some((value as f64 * scale / calls as f64).round() as u64);
+                match event {
+                    Event::Cycles => counts.cycles = value,
+                    Event::Instructions => counts.instructions = value,
+                    Event::BranchMisses => counts.branch_misses = value,
+                    Event::CacheMisses => counts.cache_misses = value,
+                }
+            }
+            counts
+        }
+    }
+}
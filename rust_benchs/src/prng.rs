@@ -0,0 +1,126 @@
+use rand::RngCore;
+use rand::rand_core::impls::{fill_bytes_via_next, next_u64_via_u32};
+use wasm_bindgen::prelude::*;
+
+// Small non-cryptographic generators, to compare against the ChaCha generator
+// behind `rand::rng()`. They all implement `RngCore`, so values are turned into
+// floats by the same `rand` code whichever generator is used.
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub enum Prng {
+    ChaCha = 0,
+    Xorshift = 1,
+    Pcg = 2,
+    SplitMix = 3,
+}
+
+impl Prng {
+    pub fn from_name(name: &str) -> Option<Prng> {
+        match name {
+            "chacha" => Some(Prng::ChaCha),
+            "xorshift" => Some(Prng::Xorshift),
+            "pcg" => Some(Prng::Pcg),
+            "splitmix" => Some(Prng::SplitMix),
+            _ => None,
+        }
+    }
+}
+
+/// SplitMix64, one addition and a 64-bit mixing function per value.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest)
+    }
+}
+
+/// Marsaglia's xorshift64 with the (13, 7, 17) shifts: only shifts and xors.
+pub struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    pub fn new(seed: u64) -> Xorshift64 {
+        // The state must never be zero, so spread the seed with SplitMix64
+        // as its authors recommend
+        let state = SplitMix64::new(seed).next_u64();
+        Xorshift64 {
+            state: state.max(1),
+        }
+    }
+}
+
+impl RngCore for Xorshift64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest)
+    }
+}
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_INCREMENT: u64 = 1442695040888963407;
+
+/// PCG32 (XSH RR): a 64-bit LCG whose output is permuted down to 32 bits, so
+/// every `u64` (and every `f64`) takes two steps.
+pub struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    pub fn new(seed: u64) -> Pcg32 {
+        let mut pcg = Pcg32 {
+            state: seed.wrapping_add(PCG_INCREMENT),
+        };
+        pcg.next_u32();
+        pcg
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(PCG_MULTIPLIER).wrapping_add(PCG_INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest)
+    }
+}
@@ -0,0 +1,341 @@
+use std::ops::{Add, Mul, Neg, Sub};
+use std::slice;
+
+use wasm_bindgen::prelude::*;
+
+// Only +, -, *, / and sqrt are used below, which are correctly rounded on
+// every target, so all runtimes must render exactly the same image.
+
+const MAX_DEPTH: u32 = 4;
+const EPSILON: f64 = 1e-6;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Vec3 {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Vec3 {
+    const fn new(x: f64, y: f64, z: f64) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    fn dot(self, other: Vec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    fn normalized(self) -> Vec3 {
+        self * (1.0 / self.length())
+    }
+
+    // Component-wise product, used to tint light by a surface colour
+    fn tint(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    fn reflect(self, normal: Vec3) -> Vec3 {
+        self - normal * (2.0 * self.dot(normal))
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, factor: f64) -> Vec3 {
+        Vec3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Ray {
+    origin: Vec3,
+    direction: Vec3,
+}
+
+impl Ray {
+    fn at(self, t: f64) -> Vec3 {
+        self.origin + self.direction * t
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Material {
+    color: Vec3,
+    // Fraction of the colour that comes from the reflected ray
+    reflectivity: f64,
+    // Use a checkerboard of `color` and its half instead of a flat colour
+    checkered: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Hit {
+    t: f64,
+    point: Vec3,
+    normal: Vec3,
+    material: Material,
+}
+
+trait Shape {
+    fn hit(&self, ray: Ray, t_max: f64) -> Option<Hit>;
+}
+
+struct Sphere {
+    center: Vec3,
+    radius: f64,
+    material: Material,
+}
+
+impl Shape for Sphere {
+    fn hit(&self, ray: Ray, t_max: f64) -> Option<Hit> {
+        let oc = ray.origin - self.center;
+        let half_b = oc.dot(ray.direction);
+        let c = oc.dot(oc) - self.radius * self.radius;
+        let discriminant = half_b * half_b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let root = discriminant.sqrt();
+        let mut t = -half_b - root;
+        if t < EPSILON {
+            t = -half_b + root;
+        }
+        if t < EPSILON || t > t_max {
+            return None;
+        }
+
+        let point = ray.at(t);
+        Some(Hit {
+            t,
+            point,
+            normal: (point - self.center) * (1.0 / self.radius),
+            material: self.material,
+        })
+    }
+}
+
+struct Plane {
+    point: Vec3,
+    normal: Vec3,
+    material: Material,
+}
+
+impl Shape for Plane {
+    fn hit(&self, ray: Ray, t_max: f64) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction);
+        if denominator.abs() < EPSILON {
+            return None;
+        }
+
+        let t = (self.point - ray.origin).dot(self.normal) / denominator;
+        if t < EPSILON || t > t_max {
+            return None;
+        }
+
+        Some(Hit {
+            t,
+            point: ray.at(t),
+            normal: self.normal,
+            material: self.material,
+        })
+    }
+}
+
+struct Scene {
+    shapes: Vec<Box<dyn Shape>>,
+    light: Vec3,
+    ambient: f64,
+}
+
+impl Scene {
+    fn fixed() -> Scene {
+        let matte = |r, g, b| Material {
+            color: Vec3::new(r, g, b),
+            reflectivity: 0.0,
+            checkered: false,
+        };
+        let mirror = |r, g, b| Material {
+            color: Vec3::new(r, g, b),
+            reflectivity: 0.6,
+            checkered: false,
+        };
+
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Plane {
+                point: Vec3::new(0.0, -1.0, 0.0),
+                normal: Vec3::new(0.0, 1.0, 0.0),
+                material: Material {
+                    color: Vec3::new(0.9, 0.9, 0.9),
+                    reflectivity: 0.2,
+                    checkered: true,
+                },
+            }),
+            Box::new(Sphere {
+                center: Vec3::new(0.0, 0.0, -4.0),
+                radius: 1.0,
+                material: mirror(0.9, 0.9, 1.0),
+            }),
+            Box::new(Sphere {
+                center: Vec3::new(-2.2, -0.3, -5.0),
+                radius: 0.7,
+                material: matte(0.9, 0.2, 0.2),
+            }),
+            Box::new(Sphere {
+                center: Vec3::new(2.0, -0.5, -3.5),
+                radius: 0.5,
+                material: matte(0.2, 0.8, 0.3),
+            }),
+            Box::new(Sphere {
+                center: Vec3::new(0.8, 1.4, -6.0),
+                radius: 1.2,
+                material: mirror(1.0, 0.8, 0.3),
+            }),
+        ];
+
+        Scene {
+            shapes,
+            light: Vec3::new(-4.0, 6.0, 1.0),
+            ambient: 0.1,
+        }
+    }
+
+    fn closest_hit(&self, ray: Ray) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
+        for shape in &self.shapes {
+            let t_max = closest.map_or(f64::INFINITY, |hit| hit.t);
+            if let Some(hit) = shape.hit(ray, t_max) {
+                closest = Some(hit);
+            }
+        }
+        closest
+    }
+
+    fn trace(&self, ray: Ray, depth: u32) -> Vec3 {
+        let Some(hit) = self.closest_hit(ray) else {
+            // Sky gradient
+            let t = 0.5 * (ray.direction.y + 1.0);
+            return Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t;
+        };
+
+        let mut color = hit.material.color;
+        if hit.material.checkered && (hit.point.x.floor() + hit.point.z.floor()) % 2.0 != 0.0 {
+            color = color * 0.5;
+        }
+
+        let to_light = self.light - hit.point;
+        let light_distance = to_light.length();
+        let light_direction = to_light * (1.0 / light_distance);
+        let shadow_ray = Ray {
+            origin: hit.point + hit.normal * EPSILON,
+            direction: light_direction,
+        };
+        let lit = self
+            .closest_hit(shadow_ray)
+            .is_none_or(|shadow| shadow.t > light_distance);
+        let diffuse = if lit {
+            hit.normal.dot(light_direction).max(0.0)
+        } else {
+            0.0
+        };
+        let local = color * (self.ambient + diffuse);
+
+        if hit.material.reflectivity == 0.0 || depth >= MAX_DEPTH {
+            return local;
+        }
+        let reflected_ray = Ray {
+            origin: hit.point + hit.normal * EPSILON,
+            direction: ray.direction.reflect(hit.normal),
+        };
+        let reflected = self.trace(reflected_ray, depth + 1).tint(color);
+        local * (1.0 - hit.material.reflectivity) + reflected * hit.material.reflectivity
+    }
+}
+
+// Small xorshift generator for the per-pixel jitter, seeded from the pixel
+// position so every pixel is sampled the same way on every run
+fn jitter(state: &mut u32) -> f64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+    *state as f64 / u32::MAX as f64
+}
+
+fn to_byte(channel: f64) -> u8 {
+    // Gamma 2, which only needs a square root
+    (channel.clamp(0.0, 1.0).sqrt() * 255.0 + 0.5) as u8
+}
+
+/// Renders the fixed scene into `output` as RGB bytes, tracing `samples`
+/// jittered rays per pixel.
+pub fn raytrace_into(output: &mut [u8], width: u32, height: u32, samples: u32) {
+    assert_eq!(
+        output.len(),
+        width as usize * height as usize * 3,
+        "output image has incorrect size"
+    );
+    let scene = Scene::fixed();
+    let origin = Vec3::new(0.0, 0.5, 1.0);
+    let aspect = width as f64 / height as f64;
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut state = (y * width + x).wrapping_mul(2654435761) | 1;
+            let mut color = Vec3::default();
+            for _ in 0..samples {
+                let u = (x as f64 + jitter(&mut state)) / width as f64;
+                let v = (y as f64 + jitter(&mut state)) / height as f64;
+                let direction = Vec3::new((2.0 * u - 1.0) * aspect, 1.0 - 2.0 * v, -1.5);
+                let ray = Ray {
+                    origin,
+                    direction: direction.normalized(),
+                };
+                color = color + scene.trace(ray, 0);
+            }
+            color = color * (1.0 / samples as f64);
+
+            let pixel = (y as usize * width as usize + x as usize) * 3;
+            output[pixel] = to_byte(color.x);
+            output[pixel + 1] = to_byte(color.y);
+            output[pixel + 2] = to_byte(color.z);
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn raytrace(width: u32, height: u32, samples: u32) -> Vec<u8> {
+    let mut output = vec![0; width as usize * height as usize * 3];
+    raytrace_into(&mut output, width, height, samples);
+    output
+}
+
+#[wasm_bindgen]
+pub fn raytrace_in_memory(output_ptr: *mut u8, width: u32, height: u32, samples: u32) {
+    let output =
+        unsafe { slice::from_raw_parts_mut(output_ptr, width as usize * height as usize * 3) };
+    raytrace_into(output, width, height, samples);
+}
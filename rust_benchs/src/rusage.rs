@@ -0,0 +1,49 @@
+// Page faults and context switches of the whole process, from `getrusage`.
+// They explain most outliers: a fault on freshly allocated memory, or the
+// scheduler taking the CPU away. Only read on Linux.
+
+/// Faults and context switches, either totals since the process started or
+/// the difference between two readings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rusage {
+    /// Faults served without I/O, e.g. the first touch of a new page.
+    pub minor_faults: u64,
+    /// Faults that had to read from disk.
+    pub major_faults: u64,
+    /// Switches because the process waited, e.g. on a lock or I/O.
+    pub voluntary_switches: u64,
+    /// Switches because the scheduler preempted the process.
+    pub involuntary_switches: u64,
+}
+
+impl Rusage {
+    /// Totals so far, or `None` when not on Linux.
+    pub fn now() -> Option<Rusage> {
+        #[cfg(target_os = "linux")]
+        {
+            // SAFETY: `getrusage` only writes into the struct passed
+            let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+            if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+                return None;
+            }
+            Some(Rusage {
+                minor_faults: usage.ru_minflt as u64,
+                major_faults: usage.ru_majflt as u64,
+                voluntary_switches: usage.ru_nvcsw as u64,
+                involuntary_switches: usage.ru_nivcsw as u64,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        None
+    }
+
+    /// What happened between `earlier` and this reading.
+    pub fn since(&self, earlier: &Rusage) -> Rusage {
+        Rusage {
+            minor_faults: self.minor_faults - earlier.minor_faults,
+            major_faults: self.major_faults - earlier.major_faults,
+            voluntary_switches: self.voluntary_switches - earlier.voluntary_switches,
+            involuntary_switches: self.involuntary_switches - earlier.involuntary_switches,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+
+use wasm_bindgen::prelude::*;
+
+// Every kernel has `threads` threads update one shared value `ops` times each.
+// With one thread the work runs on the calling thread, so the uncontended cost
+// can be measured on targets without threads too. Kernels return `None` when
+// the target can't spawn the threads asked for.
+
+// The update applied by the CAS and lock kernels. Applying it n times gives
+// the same value whichever thread applies it, so the result is deterministic.
+fn step(value: u64) -> u64 {
+    value.wrapping_mul(6364136223846793005).wrapping_add(1)
+}
+
+/// Value left by `updates` calls to the update used by `sync_atomic_cas`,
+/// `sync_mutex` and `sync_rwlock`.
+pub fn sync_expected(updates: u64) -> u64 {
+    (0..updates).fold(0, |value, _| step(value))
+}
+
+// Runs `work` on `threads` threads, returning false if any of them could not
+// be spawned
+fn run_threads(threads: u32, work: impl Fn() + Sync) -> bool {
+    if threads <= 1 {
+        work();
+        return true;
+    }
+    thread::scope(|scope| {
+        (0..threads).all(|_| thread::Builder::new().spawn_scoped(scope, &work).is_ok())
+    })
+}
+
+/// Counts with `AtomicU64::fetch_add`.
+#[wasm_bindgen]
+pub fn sync_atomic_fetch_add(threads: u32, ops: u32) -> Option<u64> {
+    let counter = AtomicU64::new(0);
+    let spawned = run_threads(threads, || {
+        for _ in 0..ops {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    spawned.then(|| counter.into_inner())
+}
+
+/// Applies the update with a `compare_exchange_weak` loop, which retries
+/// whenever another thread got there first.
+#[wasm_bindgen]
+pub fn sync_atomic_cas(threads: u32, ops: u32) -> Option<u64> {
+    let value = AtomicU64::new(0);
+    let spawned = run_threads(threads, || {
+        for _ in 0..ops {
+            let mut current = value.load(Ordering::Relaxed);
+            while let Err(actual) = value.compare_exchange_weak(
+                current,
+                step(current),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                current = actual;
+            }
+        }
+    });
+    spawned.then(|| value.into_inner())
+}
+
+/// Applies the update holding a `Mutex`.
+#[wasm_bindgen]
+pub fn sync_mutex(threads: u32, ops: u32) -> Option<u64> {
+    let value = Mutex::new(0u64);
+    let spawned = run_threads(threads, || {
+        for _ in 0..ops {
+            let mut guard = value.lock().unwrap();
+            *guard = step(*guard);
+        }
+    });
+    spawned.then(|| value.into_inner().unwrap())
+}
+
+/// Reads the value under a `RwLock`, applying the update with the write lock
+/// on one operation out of `write_every`.
+#[wasm_bindgen]
+pub fn sync_rwlock(threads: u32, ops: u32, write_every: u32) -> Option<u64> {
+    assert!(write_every > 0, "write_every must be at least 1");
+    let value = RwLock::new(0u64);
+    let spawned = run_threads(threads, || {
+        let mut read_sum: u64 = 0;
+        for i in 0..ops {
+            if i % write_every == 0 {
+                let mut guard = value.write().unwrap();
+                *guard = step(*guard);
+            } else {
+                read_sum = read_sum.wrapping_add(*value.read().unwrap());
+            }
+        }
+        // What was read depends on the interleaving, so it can't be part of
+        // the result
+        black_box(read_sum);
+    });
+    spawned.then(|| value.into_inner().unwrap())
+}
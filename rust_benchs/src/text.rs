@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::{slice, str};
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+const VOCABULARY_SIZE: usize = 4096;
+const MAX_WORD_LEN: usize = 12;
+
+// Mix of 2, 3 and 4 byte code points, with upper and lower case pairs so that
+// `to_lowercase` has real work to do outside of ASCII.
+const NON_ASCII_CHARS: [char; 16] = [
+    'á', 'É', 'ñ', 'Ü', 'ß', 'ç', 'Ж', 'д', 'Ω', 'λ', '中', '文', '日', '本', '😀', '🚀',
+];
+
+/// FNV-1a over a byte slice, used to turn text outputs into checksums.
+pub fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+fn random_word(rng: &mut StdRng, non_ascii_ratio: f64) -> String {
+    let len = rng.random_range(1..=MAX_WORD_LEN);
+    let mut word = String::with_capacity(len * 2);
+    for _ in 0..len {
+        if rng.random_bool(non_ascii_ratio) {
+            word.push(*NON_ASCII_CHARS.choose(rng).unwrap());
+        } else {
+            let letter = rng.random_range(b'a'..=b'z');
+            // Roughly one in ten ASCII letters is uppercase
+            if rng.random_ratio(1, 10) {
+                word.push(letter.to_ascii_uppercase() as char);
+            } else {
+                word.push(letter as char);
+            }
+        }
+    }
+    word
+}
+
+/// Generates a corpus of at least `len` bytes made of words drawn from a fixed
+/// vocabulary, so that words repeat and word frequencies are meaningful.
+/// `non_ascii_ratio` is the probability of each character being non-ASCII.
+#[wasm_bindgen]
+pub fn text_corpus(len: usize, non_ascii_ratio: f64, seed: u32) -> String {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let vocabulary: Vec<String> = (0..VOCABULARY_SIZE)
+        .map(|_| random_word(&mut rng, non_ascii_ratio))
+        .collect();
+
+    let mut corpus = String::with_capacity(len + MAX_WORD_LEN * 4);
+    while corpus.len() < len {
+        corpus.push_str(vocabulary.choose(&mut rng).unwrap());
+        match rng.random_range(0..20) {
+            0 => corpus.push_str(", "),
+            1 => corpus.push_str(". "),
+            2 => corpus.push('\n'),
+            _ => corpus.push(' '),
+        }
+    }
+    corpus
+}
+
+#[wasm_bindgen]
+pub fn text_utf8_validate(bytes: &[u8]) -> u32 {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.len() as u32,
+        Err(_) => 0,
+    }
+}
+
+#[wasm_bindgen]
+pub fn text_chars(text: &str) -> u32 {
+    let mut hash: u32 = 0;
+    for c in text.chars() {
+        hash = hash.rotate_left(5) ^ c as u32;
+    }
+    hash
+}
+
+#[wasm_bindgen]
+pub fn text_to_lowercase(text: &str) -> u32 {
+    let lower = text.to_lowercase();
+    fnv1a(lower.as_bytes())
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        .filter(|word| !word.is_empty())
+}
+
+#[wasm_bindgen]
+pub fn text_split_words(text: &str) -> u32 {
+    let mut count: u32 = 0;
+    let mut total_len: u32 = 0;
+    for word in words(text) {
+        count += 1;
+        total_len = total_len.wrapping_add(word.len() as u32);
+    }
+    count.wrapping_mul(31).wrapping_add(total_len)
+}
+
+/// Counts the (possibly overlapping) occurrences of `needle` in `text`,
+/// comparing byte by byte from every starting position.
+#[wasm_bindgen]
+pub fn text_search(text: &str, needle: &str) -> u32 {
+    let haystack = text.as_bytes();
+    let needle = needle.as_bytes();
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return 0;
+    }
+
+    let mut count = 0;
+    for start in 0..=haystack.len() - needle.len() {
+        let mut k = 0;
+        while k < needle.len() && haystack[start + k] == needle[k] {
+            k += 1;
+        }
+        if k == needle.len() {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Counts case-insensitive word frequencies. The checksum does not depend on
+/// the `HashMap` iteration order.
+#[wasm_bindgen]
+pub fn text_word_frequency(text: &str) -> u32 {
+    let mut frequencies: HashMap<String, u32> = HashMap::new();
+    for word in words(text) {
+        *frequencies.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+
+    frequencies.iter().fold(0, |acc: u32, (word, &count)| {
+        acc.wrapping_add(fnv1a(word.as_bytes()).wrapping_mul(count))
+    })
+}
+
+/// The text at `ptr`, for the `*_in_memory` exports. The web harness copies
+/// every input into linear memory once, before the benchmark, and the timed
+/// calls only get a pointer to it: passing a `&str` or a slice to an export
+/// instead has wasm_bindgen copy the whole input in on every call. The caller
+/// must have copied the text as UTF-8 (such as the output of `TextEncoder`),
+/// which isn't validated again, so that only the kernel is timed.
+pub(crate) unsafe fn str_in_memory<'a>(ptr: *const u8, len: usize) -> &'a str {
+    unsafe { str::from_utf8_unchecked(slice::from_raw_parts(ptr, len)) }
+}
+
+#[wasm_bindgen]
+pub fn text_utf8_validate_in_memory(bytes_ptr: *const u8, len: usize) -> u32 {
+    text_utf8_validate(unsafe { slice::from_raw_parts(bytes_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn text_chars_in_memory(text_ptr: *const u8, len: usize) -> u32 {
+    text_chars(unsafe { str_in_memory(text_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn text_to_lowercase_in_memory(text_ptr: *const u8, len: usize) -> u32 {
+    text_to_lowercase(unsafe { str_in_memory(text_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn text_split_words_in_memory(text_ptr: *const u8, len: usize) -> u32 {
+    text_split_words(unsafe { str_in_memory(text_ptr, len) })
+}
+
+#[wasm_bindgen]
+pub fn text_word_frequency_in_memory(text_ptr: *const u8, len: usize) -> u32 {
+    text_word_frequency(unsafe { str_in_memory(text_ptr, len) })
+}
+
+/// The needle is short, so unlike the text it is still passed as a string.
+#[wasm_bindgen]
+pub fn text_search_in_memory(text_ptr: *const u8, len: usize, needle: &str) -> u32 {
+    text_search(unsafe { str_in_memory(text_ptr, len) }, needle)
+}
@@ -0,0 +1,349 @@
+//! Checks that the harness measures the work of every kernel, rather than
+//! whatever the optimizer left of it. The tests are built at `opt-level = 3`
+//! (see `[profile.test]`), and each kernel is timed through `Harness::measure`
+//! on a small and a large input: a kernel whose work was eliminated takes the
+//! same time on both.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use rust_benchs::abstraction::*;
+use rust_benchs::bignum::*;
+use rust_benchs::dynprog::*;
+use rust_benchs::graph::*;
+use rust_benchs::harness::Harness;
+use rust_benchs::image::*;
+use rust_benchs::intarith::*;
+use rust_benchs::json::*;
+use rust_benchs::memory::*;
+use rust_benchs::prng::Prng;
+use rust_benchs::raytracer::*;
+use rust_benchs::sync::*;
+use rust_benchs::text::*;
+use rust_benchs::*;
+
+const EXECUTIONS: u64 = 5;
+// The large inputs need at least 16 times the work of the small ones, so
+// requiring 4 times the time leaves plenty of room for noise
+const MIN_RATIO: u128 = 4;
+
+// Timings from tests running in parallel would disturb each other
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn serial() -> MutexGuard<'static, ()> {
+    // A failed test poisons the lock, which must not fail the others
+    SERIAL.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn harness() -> Harness {
+    Harness {
+        warmup_iterations: 1,
+        warmup_time: Duration::from_secs(1),
+        ..Harness::default()
+    }
+}
+
+fn min_time<I: ?Sized, S, O>(
+    input: &I,
+    mut state: S,
+    run: impl FnMut(&I, &mut S) -> O,
+    after: impl FnMut(&mut S, O),
+) -> u128 {
+    let measurement = harness().measure(EXECUTIONS, "test", input, &mut state, run, after);
+    measurement.times.into_iter().min().unwrap()
+}
+
+fn assert_scales(name: &str, small: u128, large: u128) {
+    assert!(
+        large >= small.max(1) * MIN_RATIO,
+        "{}: {} ns for the large input against {} ns for the small one, the work seems to be \
+         optimized away",
+        name,
+        large,
+        small
+    );
+}
+
+fn check<I: ?Sized, O>(name: &str, small: &I, large: &I, fun: impl Fn(&I) -> O) {
+    let time = |input: &I| min_time(input, (), |input, _| fun(input), |_, _| ());
+    assert_scales(name, time(small), time(large));
+}
+
+// For kernels writing into a buffer, which is part of the state
+fn check_with_state<I: ?Sized, S>(
+    name: &str,
+    small: (&I, S),
+    large: (&I, S),
+    fun: impl Fn(&I, &mut S),
+) {
+    let time = |(input, state): (&I, S)| min_time(input, state, &fun, |_, ()| ());
+    assert_scales(name, time(small), time(large));
+}
+
+#[test]
+fn pure_loop() {
+    // Without the harness's black boxes this loop is removed entirely, as its
+    // result is unused, or folded away, as its input is a constant
+    let _serial = serial();
+    check("pure_loop", &100_000u64, &1_600_000u64, |&n| {
+        (0..n).fold(0u64, |hash, i| hash.rotate_left(7) ^ i)
+    });
+}
+
+#[test]
+fn scalar_kernels() {
+    let _serial = serial();
+    check("fibonacci", &20, &26, |&n| fibonacci(n));
+    check("fibonacci_recursive", &20, &26, |&n| fibonacci_recursive(n));
+    check("eratostenes", &100_000, &1_600_000, |&n| eratostenes(n));
+    check("eratostenes_bitpacked", &100_000, &1_600_000, |&n| {
+        eratostenes_bitpacked(n)
+    });
+    check("eratostenes_odds", &100_000, &1_600_000, |&n| {
+        eratostenes_odds(n)
+    });
+    check("eratostenes_segmented", &100_000, &1_600_000, |&n| {
+        eratostenes_segmented(n)
+    });
+    check("eratostenes_primes", &100_000, &1_600_000, |&n| {
+        eratostenes_primes(n)
+    });
+    check("montecarlo", &10_000.0, &160_000.0, |&n| montecarlo(n));
+    check("montecarlo_prng", &10_000.0, &160_000.0, |&n| {
+        montecarlo_prng(n, Prng::Xorshift, 42)
+    });
+    check("nqueens", &8, &10, |&n| nqueens(n));
+    check("nqueens_bitmask", &9, &11, |&n| nqueens_bitmask(n));
+    check("nqueens_fundamental", &9, &11, |&n| nqueens_fundamental(n));
+    check("nqueens_solutions", &8, &10, |&n| nqueens_solutions(n));
+    check("bignum_factorial", &500, &2000, |&n| {
+        bignum_factorial_value(n)
+    });
+    check("bignum_power", &2000, &32_000, |&n| {
+        bignum_power_value(3, n)
+    });
+    check("bignum_pi", &250, &1000, |&n| bignum_pi_value(n));
+}
+
+#[test]
+fn buffer_kernels() {
+    let _serial = serial();
+
+    // Sorts a copy of the input, restored outside of the timed region, so
+    // calls can't be batched
+    let unbatched = Harness {
+        min_sample_time: Duration::ZERO,
+        ..harness()
+    };
+    let time_quicksort = |input: &[i32]| {
+        let measurement = unbatched.measure(
+            EXECUTIONS,
+            "quicksort",
+            &(),
+            &mut input.to_vec(),
+            |_, data| quicksort(data),
+            |data, ()| data.copy_from_slice(input),
+        );
+        measurement.times.into_iter().min().unwrap()
+    };
+    let small: Vec<i32> = abstraction_data(10_000, 1)
+        .iter()
+        .map(|&v| v as i32)
+        .collect();
+    let large: Vec<i32> = abstraction_data(160_000, 1)
+        .iter()
+        .map(|&v| v as i32)
+        .collect();
+    assert_scales("quicksort", time_quicksort(&small), time_quicksort(&large));
+
+    let matrices = |width: usize| {
+        let a = vec![1.5; width * width];
+        let b = vec![0.5; width * width];
+        let c = vec![0.0; output_matrix_size(width, a.len())];
+        ((a, b, width), c)
+    };
+    let (small, small_c) = matrices(20);
+    let (large, large_c) = matrices(50);
+    check_with_state(
+        "matrix_multiply",
+        (&small, small_c),
+        (&large, large_c),
+        |(a, b, width), c| matrix_multiply(a, b, c, *width),
+    );
+
+    check("mandelbrot", &(64, 48), &(256, 192), |&(width, height)| {
+        mandelbrot_view(width, height, 100, -0.75, 0.0, 1.0)
+    });
+    check(
+        "mandelbrot_f32",
+        &(64, 48),
+        &(256, 192),
+        |&(width, height)| mandelbrot_view_f32(width, height, 100, -0.75, 0.0, 1.0),
+    );
+    check("raytrace", &(32, 24), &(128, 96), |&(width, height)| {
+        raytrace(width, height, 1)
+    });
+
+    for filter in [ImageFilter::Box, ImageFilter::Gaussian, ImageFilter::Sobel] {
+        let image = |size: u32| {
+            (
+                image_generate(size, size, 1),
+                vec![0; (size * size) as usize],
+            )
+        };
+        let ((small, small_output), (large, large_output)) = (image(64), image(256));
+        check_with_state(
+            &format!("image_filter_u8 {:?}", filter),
+            (&small[..], small_output),
+            (&large[..], large_output),
+            |input, output| {
+                let size = input.len().isqrt() as u32;
+                image_filter_u8(input, output, size, size, filter)
+            },
+        );
+
+        let image = |size: u32| {
+            let image = image_to_f32(&image_generate(size, size, 1));
+            (image, vec![0.0; (size * size) as usize])
+        };
+        let ((small, small_output), (large, large_output)) = (image(64), image(256));
+        check_with_state(
+            &format!("image_filter_f32 {:?}", filter),
+            (&small[..], small_output),
+            (&large[..], large_output),
+            |input, output| {
+                let size = input.len().isqrt() as u32;
+                image_filter_f32(input, output, size, size, filter)
+            },
+        );
+    }
+
+    let board = |size: u32| {
+        (
+            life_generate(size, size, 1),
+            vec![0; (size * size) as usize],
+        )
+    };
+    let ((small, small_output), (large, large_output)) = (board(64), board(256));
+    check_with_state(
+        "life_steps",
+        (&small[..], small_output),
+        (&large[..], large_output),
+        |input, output| {
+            let size = input.len().isqrt() as u32;
+            life_steps(input, output, size, size, 4)
+        },
+    );
+}
+
+#[test]
+fn text_kernels() {
+    let _serial = serial();
+    let small = text_corpus(10_000, 0.1, 1);
+    let large = text_corpus(160_000, 0.1, 1);
+    check(
+        "text_utf8_validate",
+        small.as_bytes(),
+        large.as_bytes(),
+        text_utf8_validate,
+    );
+    let kernels = [
+        ("text_chars", text_chars as fn(&str) -> u32),
+        ("text_to_lowercase", text_to_lowercase),
+        ("text_split_words", text_split_words),
+        ("text_search", |text| text_search(text, "the")),
+        ("text_word_frequency", text_word_frequency),
+    ];
+    for (name, fun) in kernels {
+        check(name, &small[..], &large[..], fun);
+    }
+
+    let small = json_document(100, "nested", 1);
+    let large = json_document(1600, "nested", 1);
+    check("json_value_roundtrip", &small[..], &large[..], |document| {
+        json_value_roundtrip(document)
+    });
+    check("json_typed_roundtrip", &small[..], &large[..], |document| {
+        json_typed_roundtrip(document)
+    });
+}
+
+#[test]
+fn sequence_and_graph_kernels() {
+    let _serial = serial();
+    let sequences = |len| (dp_sequence(len, 4, 1), dp_sequence(len, 4, 2));
+    let (small, large) = (sequences(200), sequences(800));
+    let kernels = [
+        (
+            "levenshtein_full",
+            levenshtein_full as fn(&[u8], &[u8]) -> u32,
+        ),
+        ("levenshtein_two_rows", levenshtein_two_rows),
+        ("lcs_full", lcs_full),
+        ("lcs_two_rows", lcs_two_rows),
+    ];
+    for (name, fun) in kernels {
+        check(name, &small, &large, |(a, b)| fun(a, b));
+    }
+
+    let small = CsrGraph::random(10_000, 40_000, 1);
+    let large = CsrGraph::random(160_000, 640_000, 1);
+    check("graph_bfs", &small, &large, |graph| graph_bfs(graph, 0));
+    check("graph_dijkstra", &small, &large, |graph| {
+        graph_dijkstra(graph, 0)
+    });
+}
+
+#[test]
+fn data_kernels() {
+    let _serial = serial();
+    let small = abstraction_data(10_000, 1);
+    let large = abstraction_data(160_000, 1);
+    let kernels = [
+        (
+            "abstraction_iterator",
+            abstraction_iterator as fn(&[u32]) -> u32,
+        ),
+        ("abstraction_index", abstraction_index),
+        ("abstraction_generic", abstraction_generic),
+        ("abstraction_dyn", abstraction_dyn),
+        ("abstraction_closure", abstraction_closure),
+        ("abstraction_fn_pointer", abstraction_fn_pointer),
+        ("intarith_div_i32", intarith_div_i32),
+        ("intarith_div_i64", intarith_div_i64),
+        ("intarith_checked", intarith_checked),
+        ("intarith_count_primes", intarith_count_primes),
+    ];
+    for (name, fun) in kernels {
+        check(name, &small[..], &large[..], fun);
+    }
+}
+
+#[test]
+fn sync_and_memory_kernels() {
+    let _serial = serial();
+    let kernels = [
+        (
+            "sync_atomic_fetch_add",
+            sync_atomic_fetch_add as fn(u32, u32) -> Option<u64>,
+        ),
+        ("sync_atomic_cas", sync_atomic_cas),
+        ("sync_mutex", sync_mutex),
+        ("sync_rwlock", |threads, ops| sync_rwlock(threads, ops, 10)),
+    ];
+    for (name, fun) in kernels {
+        check(name, &10_000, &160_000, |&ops| fun(1, ops).unwrap());
+    }
+
+    let kernels = [
+        ("memory_push", memory_push as fn(u32) -> u32),
+        ("memory_push_with_capacity", memory_push_with_capacity),
+        ("memory_extend", |count| memory_extend(count, 1000)),
+        ("memory_mixed_alloc", |rounds| {
+            memory_mixed_alloc(rounds / 100, 1)
+        }),
+    ];
+    for (name, fun) in kernels {
+        check(name, &10_000, &160_000, |&count| fun(count));
+    }
+}
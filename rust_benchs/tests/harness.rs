@@ -0,0 +1,69 @@
+use rust_benchs::harness::{Measurement, MedianInterval, Outlier, OutlierCause, ProcessVariance};
+use rust_benchs::rusage::Rusage;
+
+#[test]
+fn median_interval() {
+    // Ranks 5 and 16 bound the median of 20 values with 95% confidence
+    let times: Vec<u128> = (1..=20).rev().collect();
+    let interval = MedianInterval::of(&times).unwrap();
+    assert_eq!(
+        interval,
+        MedianInterval {
+            median: 10.5,
+            low: 5,
+            high: 16,
+        }
+    );
+    assert_eq!(interval.relative_width(), 0.5238095238095238);
+
+    // Times below the timer's resolution never look converged
+    let zeros = MedianInterval::of(&[0; 20]).unwrap();
+    assert_eq!(zeros.relative_width(), f64::INFINITY);
+
+    assert_eq!(MedianInterval::of(&[3, 1, 2]), None);
+}
+
+#[test]
+fn process_variance() {
+    // Means 2 and 12, each process with a variance of 1
+    let processes = vec![vec![1, 2, 3], vec![11, 12, 13]];
+    assert_eq!(
+        ProcessVariance::of(&processes),
+        Some(ProcessVariance {
+            between: 50.0,
+            within: 1.0,
+        })
+    );
+
+    assert_eq!(ProcessVariance::of(&processes[..1]), None);
+    assert_eq!(ProcessVariance::of(&[vec![1, 2], vec![3]]), None);
+}
+
+#[test]
+fn outlier_causes() {
+    let faults = |minor_faults| Rusage {
+        minor_faults,
+        ..Rusage::default()
+    };
+    let mut measurement = Measurement {
+        times: (0..20).map(|i| 95 + i % 10).collect(),
+        rusage: vec![faults(0); 20],
+        ..Measurement::default()
+    };
+    measurement.times[4] = 300;
+    measurement.rusage[4] = faults(2000);
+    measurement.times[7] = 250;
+    assert_eq!(
+        measurement.outliers(),
+        vec![
+            Outlier {
+                sample: 4,
+                cause: OutlierCause::MinorFaults,
+            },
+            Outlier {
+                sample: 7,
+                cause: OutlierCause::Unknown,
+            },
+        ]
+    );
+}
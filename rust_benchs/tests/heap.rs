@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use rust_benchs::harness::Harness;
+use rust_benchs::heap::{CountingAlloc, HeapStats};
+use rust_benchs::memory::memory_push_with_capacity;
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+#[test]
+fn counts_heap_per_call() {
+    let harness = Harness {
+        warmup_iterations: 1,
+        warmup_time: Duration::from_secs(1),
+        ..Harness::default()
+    };
+    let measurement = harness.measure(
+        3,
+        "memory_push_with_capacity",
+        &1000,
+        &mut (),
+        |&count, _| memory_push_with_capacity(count),
+        |_, _| (),
+    );
+
+    // One `Vec` of 1000 u32, freed before the call returns
+    let expected = HeapStats {
+        allocations: 1,
+        bytes: 4000,
+        peak_live: 4000,
+    };
+    assert_eq!(measurement.heap, vec![expected; 3]);
+}
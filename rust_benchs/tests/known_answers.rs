@@ -0,0 +1,220 @@
+//! Checks every kernel against answers known independently of it: published
+//! sequences, textbook examples, or values small enough to work out by hand.
+
+use rust_benchs::bignum::*;
+use rust_benchs::dynprog::*;
+use rust_benchs::graph::*;
+use rust_benchs::json::*;
+use rust_benchs::text::fnv1a;
+use rust_benchs::*;
+
+#[test]
+fn fibonacci_values() {
+    let known = [
+        (0, 0),
+        (1, 1),
+        (2, 1),
+        (10, 55),
+        (20, 6765),
+        (50, 12586269025),
+    ];
+    for (n, expected) in known {
+        if n <= 20 {
+            assert_eq!(fibonacci(n) as u64, expected);
+            assert_eq!(fibonacci_recursive(n), Some(expected));
+        }
+        assert_eq!(fibonacci_iterative(n), Some(expected));
+        assert_eq!(fibonacci_memoized(n), Some(expected));
+        assert_eq!(fibonacci_matrix(n), Some(expected));
+        assert_eq!(fibonacci_tail(n), Some(expected));
+    }
+
+    // F(93) is the largest Fibonacci number below 2^64
+    let largest = Some(12200160415121876738);
+    assert_eq!(FIBONACCI_MAX_N, 93);
+    assert_eq!(fibonacci_iterative(FIBONACCI_MAX_N), largest);
+    assert_eq!(fibonacci_memoized(FIBONACCI_MAX_N), largest);
+    assert_eq!(fibonacci_matrix(FIBONACCI_MAX_N), largest);
+    assert_eq!(fibonacci_tail(FIBONACCI_MAX_N), largest);
+    for fun in [
+        fibonacci_recursive,
+        fibonacci_iterative,
+        fibonacci_memoized,
+        fibonacci_matrix,
+        fibonacci_tail,
+    ] {
+        assert_eq!(fun(FIBONACCI_MAX_N + 1), None);
+        assert_eq!(fun(u32::MAX), None);
+    }
+}
+
+#[test]
+fn sieve_prime_counts() {
+    // pi(n), the number of primes up to n
+    let known = [
+        (0, 0),
+        (1, 0),
+        (2, 1),
+        (10, 4),
+        (100, 25),
+        (1000, 168),
+        (1_000_000, 78498),
+    ];
+    for (limit, expected) in known {
+        assert_eq!(eratostenes(limit), expected, "eratostenes({limit})");
+        assert_eq!(
+            eratostenes_bitpacked(limit),
+            expected,
+            "eratostenes_bitpacked({limit})"
+        );
+        assert_eq!(
+            eratostenes_odds(limit),
+            expected,
+            "eratostenes_odds({limit})"
+        );
+        assert_eq!(
+            eratostenes_segmented(limit),
+            expected,
+            "eratostenes_segmented({limit})"
+        );
+        assert_eq!(
+            eratostenes_primes(limit).len() as u32,
+            expected,
+            "eratostenes_primes({limit})"
+        );
+    }
+    assert_eq!(eratostenes_primes(30), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+}
+
+#[test]
+fn nqueens_counts() {
+    // OEIS A000170 and A002562, from n = 1
+    let all = [1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+    let fundamental = [1, 0, 0, 1, 2, 1, 6, 12, 46, 92, 341, 1787];
+    for n in 1..=all.len() as u32 {
+        let expected = all[n as usize - 1];
+        assert_eq!(nqueens(n), expected, "nqueens({n})");
+        assert_eq!(nqueens_bitmask(n), expected, "nqueens_bitmask({n})");
+        assert_eq!(NQUEENS_SOLUTIONS[n as usize], expected);
+        let expected = fundamental[n as usize - 1];
+        assert_eq!(nqueens_fundamental(n), expected, "nqueens_fundamental({n})");
+        assert_eq!(NQUEENS_FUNDAMENTAL_SOLUTIONS[n as usize], expected);
+    }
+
+    // Every placement is a distinct solution: one queen per row and column,
+    // none sharing a diagonal
+    for n in 1..=8 {
+        let solutions = nqueens_solutions(n);
+        let placements = solutions.placements();
+        assert_eq!(solutions.count(), all[n as usize - 1]);
+        assert_eq!(placements.len(), (solutions.count() * n) as usize);
+        let boards: Vec<&[u32]> = placements.chunks(n as usize).collect();
+        for board in &boards {
+            for (row, &column) in board.iter().enumerate() {
+                assert!(column < n);
+                for (other_row, &other_column) in board.iter().enumerate().skip(row + 1) {
+                    assert_ne!(column, other_column);
+                    assert_ne!(column.abs_diff(other_column) as usize, other_row - row);
+                }
+            }
+        }
+        let mut distinct = boards.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), boards.len());
+    }
+}
+
+#[test]
+fn edit_distances() {
+    let levenshtein = [
+        ("kitten", "sitting", 3),
+        ("flaw", "lawn", 2),
+        ("", "abc", 3),
+        ("abc", "abc", 0),
+    ];
+    for (a, b, expected) in levenshtein {
+        assert_eq!(
+            levenshtein_full(a.as_bytes(), b.as_bytes()),
+            expected,
+            "{a} {b}"
+        );
+        assert_eq!(
+            levenshtein_two_rows(a.as_bytes(), b.as_bytes()),
+            expected,
+            "{a} {b}"
+        );
+    }
+    let lcs = [
+        ("ABCBDAB", "BDCABA", 4),
+        ("AGGTAB", "GXTXAYB", 4),
+        ("", "abc", 0),
+        ("abc", "def", 0),
+    ];
+    for (a, b, expected) in lcs {
+        assert_eq!(lcs_full(a.as_bytes(), b.as_bytes()), expected, "{a} {b}");
+        assert_eq!(
+            lcs_two_rows(a.as_bytes(), b.as_bytes()),
+            expected,
+            "{a} {b}"
+        );
+    }
+}
+
+#[test]
+fn graph_distances() {
+    // The checksum is the sum of the distances times 31, plus the number of
+    // vertices reached
+    let checksum = |distances: &[u32]| distances.iter().sum::<u32>() * 31 + distances.len() as u32;
+
+    // The direct edge 0 -> 1 is the shortest in edges but not in weight, and
+    // vertex 4 is never reached
+    let edges = [(0, 1, 4), (0, 2, 1), (2, 1, 1), (1, 3, 1)];
+    let graph = CsrGraph::from_edges(5, &edges);
+    assert_eq!(graph_bfs(&graph, 0), checksum(&[0, 1, 1, 2]));
+    assert_eq!(graph_dijkstra(&graph, 0), checksum(&[0, 2, 1, 3]));
+    assert_eq!(graph_bfs(&graph, 4), checksum(&[0]));
+
+    // On a grid, the corner (0, 0) is x + y edges away from cell (x, y)
+    let (width, height) = (30, 20);
+    let grid = CsrGraph::grid(width, height, 1);
+    let distances: Vec<u32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| x + y))
+        .collect();
+    assert_eq!(graph_bfs(&grid, 0), checksum(&distances));
+}
+
+#[test]
+fn json_roundtrips() {
+    // The typed structs serialize their fields in the order they were
+    // generated in, so the round trip gives back the document unchanged. Only
+    // without numbers, though: serde_json's default float parsing can be one
+    // ulp off, and exact parsing (its `float_roundtrip` feature) isn't what
+    // the benchmarks measure
+    let document = json_document(20, "strings", 42);
+    assert_eq!(json_typed_roundtrip(&document), fnv1a(document.as_bytes()));
+    // Values sort object keys, so this only holds for a document with sorted
+    // keys
+    let document = r#"{"a":[1,2.5,-3,"x\"y\\z\n"],"b":{"c":null,"d":true},"e":"é中😀"}"#;
+    assert_eq!(json_value_roundtrip(document), fnv1a(document.as_bytes()));
+}
+
+#[test]
+fn bignum_values() {
+    assert_eq!(bignum_factorial(0), "1");
+    assert_eq!(bignum_factorial(20), "2432902008176640000");
+    assert_eq!(bignum_factorial(30), "265252859812191058636308480000000");
+    assert_eq!(bignum_power(2, 64), "18446744073709551616");
+    assert_eq!(bignum_power(3, 40), "12157665459056928801");
+    assert_eq!(bignum_power(7, 0), "1");
+
+    let pi = "3.1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+    assert_eq!(bignum_pi(100), pi);
+    for digits in 0..100 {
+        assert_eq!(
+            bignum_pi(digits),
+            pi[..digits as usize + 2],
+            "{digits} digits"
+        );
+    }
+}
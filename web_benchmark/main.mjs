@@ -54,7 +54,9 @@ async function runServer() {
       console.error(`${driver.name()} Page error: ${exception.stack}`);
     });
 
-    await page.goto(`http://localhost:${port}`);
+    // Set DUMP_IMAGES to also write the images of the mandelbrot benchmarks
+    const query = process.env.DUMP_IMAGES ? '?dump_images' : '';
+    await page.goto(`http://localhost:${port}/${query}`);
   });
 }
 
@@ -63,6 +65,12 @@ async function writeJsonFile(filename, data) {
   console.log(`Wrote results to ${filename}`);
 }
 
+// The page sends the images already encoded as PGM, in base64
+async function writePgmFile(filename, base64) {
+  await fs.writeFile(filename, Buffer.from(base64, 'base64'));
+  console.log(`Wrote image to ${filename}`);
+}
+
 const platform = os.platform();
 
 const FIREFOX_PATH = platform === 'darwin' 
@@ -106,7 +114,16 @@ const RUNS = 1;
       // Now just keep the times
       console.log(`Processing ${browser} - ${fun}...`);
       console.log(by_function[fun]);
-      const times = by_function[fun].flatMap(r => r.times).map(t => t * 1e6); // convert ms to ns
+      const times = by_function[fun].flatMap(r => r.times ?? []).map(t => t * 1e6); // convert ms to ns
+
+      // Some kernels send extra values, such as an estimate or memory usage,
+      // in separate report messages
+      for (const {report, values} of by_function[fun].filter(r => r.report !== undefined)) {
+        if (report === "image")
+          await writePgmFile(`image_${browser}_${fun.replace(/\W+/g, '_')}.pgm`, values.pgm);
+        else
+          await writeJsonFile(`benchmark_${report}_${browser}_${fun.replace(/\W+/g, '_')}.json`, values);
+      }
 
       by_function[fun] = times;
       await writeJsonFile(`benchmark_times_${browser}_${fun.replace(/\W+/g, '_')}.json`, times);
@@ -1,14 +1,61 @@
-import init, {fibonacci, sort_in_memory, eratostenes, alloc, dealloc, montecarlo,
-  nqueens, matrix_multiply_in_memory, allocf64, deallocf64, mandelbrot_in_memory, output_matrix_size,
-  init_panic_hook
+import init, {fibonacci, memory_push, memory_push_with_capacity, memory_extend,
+  memory_mixed_alloc, memory_pages, memory_growing_calls, sync_atomic_fetch_add, sync_atomic_cas, sync_mutex, sync_rwlock, intarith_data, intarith_div_i32_in_memory, intarith_div_i64_in_memory,
+  intarith_checked_in_memory, intarith_count_primes_in_memory, abstraction_data, allocu32, deallocu32, abstraction_iterator_in_memory, abstraction_index_in_memory,
+  abstraction_generic_in_memory, abstraction_dyn_in_memory, abstraction_closure_in_memory, abstraction_fn_pointer_in_memory, sort_in_memory, eratostenes, alloc, dealloc, montecarlo, montecarlo_prng, Prng,
+  nqueens, matrix_multiply_in_memory, allocf64, deallocf64, mandelbrot_view_in_memory, mandelbrot_view_f32_in_memory,
+  output_matrix_size,
+  allocu8, deallocu8, text_corpus, text_utf8_validate_in_memory, text_chars_in_memory, text_to_lowercase_in_memory, text_split_words_in_memory, text_search_in_memory,
+  text_word_frequency_in_memory, json_document, json_value_roundtrip_in_memory, json_typed_roundtrip_in_memory,
+  dp_sequence, levenshtein_full_in_memory, levenshtein_two_rows_in_memory, lcs_full_in_memory, lcs_two_rows_in_memory,
+  CsrGraph, graph_bfs, graph_dijkstra,
+  allocf32, deallocf32, ImageFilter, image_generate, image_to_f32,
+  image_filter_u8_in_memory, image_filter_f32_in_memory, life_generate, life_in_memory,
+  raytrace_in_memory, bignum_factorial_value, bignum_power_value, bignum_pi_value,
+  fibonacci_recursive, fibonacci_iterative, fibonacci_memoized, fibonacci_matrix, fibonacci_tail,
+  nqueens_bitmask, nqueens_fundamental, nqueens_solutions,
+  eratostenes_bitpacked, eratostenes_odds, eratostenes_segmented, eratostenes_primes, init_panic_hook
 } from './rust_benchs_pkg/rust_benchs.js';
 
 const {memory} = await init();
 
 init_panic_hook();
 
+// Open the page with ?dump_images to also send the images computed by the
+// image-producing benchmarks, as DUMP_IMAGES does in the native harness
+const dump_images = new URLSearchParams(location.search).has("dump_images");
+
 const WARMUP_ITERS = 100;
 const WARMUP_TIME = 30000; // ms
+// Warmup also ends once the median of the last WARMUP_WINDOW times is within
+// WARMUP_TOLERANCE of the median of the WARMUP_WINDOW before them, as in the
+// native harness
+const WARMUP_WINDOW = 10;
+const WARMUP_TOLERANCE = 0.05;
+
+function median(values) {
+  const sorted = [...values].sort((a, b) => a - b);
+  const mid = Math.floor(sorted.length / 2);
+  return sorted.length % 2 === 1 ? sorted[mid] : (sorted[mid - 1] + sorted[mid]) / 2;
+}
+
+function warmup_steady(times) {
+  if (times.length < 2 * WARMUP_WINDOW) return false;
+  const previous = median(times.slice(-2 * WARMUP_WINDOW, -WARMUP_WINDOW));
+  const last = median(times.slice(-WARMUP_WINDOW));
+  return Math.abs(last - previous) <= WARMUP_TOLERANCE * previous;
+}
+
+// Why the warmup with these times, started at warmup_beginning, should end, or
+// undefined to keep going
+function warmup_end(times, warmup_beginning) {
+  if (warmup_steady(times)) return "steady";
+  if (times.length === WARMUP_ITERS) return "iterations";
+  if (performance.now() - warmup_beginning >= WARMUP_TIME) return "time";
+}
+
+function log_warmup(name, end, times) {
+  console.log(JSON.stringify({ fun: name, report: "warmup", values: {end, times: times.map(t => t * 1e6)}}));
+}
 
 function benchmark(executions, name, fun, ...args) {
   console.warn(`Benchmarking ${name}, executing ${executions} times...`);
@@ -17,19 +64,23 @@ function benchmark(executions, name, fun, ...args) {
   let accumulator = 0;
   let i = 0;
 
-  // Warmup phase, to avoid initial jit/wasm costs, max 100 iterations or 3 seconds
+  // Warmup phase, to avoid initial jit/wasm costs, until the times are steady,
+  // max 100 iterations or 30 seconds
+  const warmup_times = [];
   const warmup_beginning = performance.now();
-  while (true) {
+  let end_reason;
+  while (!(end_reason = warmup_end(warmup_times, warmup_beginning))) {
+    const start = performance.now();
     const result = fun(...args);
     const end = performance.now();
     
     // Accumulate result to avoid being optimized out
     accumulator += result;
-    i++;
-    if (i === WARMUP_ITERS || end - warmup_beginning >= WARMUP_TIME) break
+    warmup_times.push(end - start);
   }
-  const warmup_end = performance.now();
-  console.warn(`Warmup done, executed ${i} iterations (${(warmup_end - warmup_beginning).toFixed(2)} ms)`);
+  const warmup_done = performance.now();
+  console.warn(`Warmup done, executed ${warmup_times.length} iterations (${end_reason}, ${(warmup_done - warmup_beginning).toFixed(2)} ms)`);
+  log_warmup(name, end_reason, warmup_times);
 
   for (i = 0; i < executions; i++) {
     const start = performance.now();
@@ -52,6 +103,7 @@ function benchmark(executions, name, fun, ...args) {
 
   console.log(JSON.stringify({ fun: name, times}));
   console.warn(`${name} benchmark done.`);
+  return times;
 }
 
 function benchmark_2(executions, name, fun, ...args) {
@@ -60,17 +112,18 @@ function benchmark_2(executions, name, fun, ...args) {
   const times = [];
   let i = 0;
 
-  // Warmup phase, to avoid initial jit/wasm costs, max 100 iterations or 3 seconds
-  let warmup_time = 0;
-  while (true) {
-    // Accumulate result to avoid being optimized out
-    warmup_time += fun(...args);
-    
-    i++;
-    if (i === WARMUP_ITERS || warmup_time >= WARMUP_TIME) break
+  // Warmup phase, to avoid initial jit/wasm costs, until the times are steady,
+  // max 100 iterations or 30 seconds
+  const warmup_times = [];
+  const warmup_beginning = performance.now();
+  let end_reason;
+  while (!(end_reason = warmup_end(warmup_times, warmup_beginning))) {
+    const [iter_time] = fun(...args);
+    warmup_times.push(iter_time);
   }
 
-  console.warn(`Warmup time: ${warmup_time}`);
+  console.warn(`Warmup done, executed ${warmup_times.length} iterations (${end_reason}), time: ${warmup_times.reduce((a, b) => a + b, 0)}`);
+  log_warmup(name, end_reason, warmup_times);
   let time_acc = 0;
   let accumulator = 0;
   for (i = 0; i < executions; i++) {
@@ -95,6 +148,22 @@ function benchmark_2(executions, name, fun, ...args) {
   console.warn(`${name} benchmark done.`);
 }
 
+// Copies the input of a benchmark into wasm memory once, so that the timed
+// calls only get its pointer. Passing a typed array or a string to an export
+// instead has wasm_bindgen copy it into the module on every call.
+function to_wasm_memory(ArrayType, alloc_fn, data) {
+  const pointer = alloc_fn(data.length);
+  new ArrayType(memory.buffer, pointer, data.length).set(data);
+  return pointer;
+}
+
+// Same for text, copied as the UTF-8 the kernels read. Returns the pointer and
+// the length in bytes.
+function text_to_wasm_memory(text) {
+  const bytes = new TextEncoder().encode(text);
+  return [to_wasm_memory(Uint8Array, allocu8, bytes), bytes.length];
+}
+
 function qsort_with_alloc(arr) {
   const jsData = new Int32Array(arr);
   const pointer = alloc(jsData.length);
@@ -150,11 +219,44 @@ function matrix_multiply_with_alloc(allocated_args, width) {
   return [end - start, returnArray];
 }
 
-function mandelbrot_with_alloc(width, height, max_iter) {
+// The image a mandelbrot kernel renders, outside of any timing
+function mandelbrot_pixels(kernel, width, height, max_iter, center_x, center_y, zoom) {
+  const pointer = alloc(width * height);
+  kernel(pointer, width, height, max_iter, center_x, center_y, zoom);
+  const pixels = new Uint32Array(memory.buffer, pointer, width * height).slice();
+  dealloc(pointer, width * height);
+  return pixels;
+}
+
+// Same binary PGM as write_pgm in the native harness, so the images from every
+// runtime can be compared byte for byte
+function pgm_image(width, height, max_value, pixels) {
+  max_value = Math.min(Math.max(max_value, 1), 65535);
+  const wide = max_value > 255;
+  const header = new TextEncoder().encode(`P5\n${width} ${height}\n${max_value}\n`);
+  const image = new Uint8Array(header.length + pixels.length * (wide ? 2 : 1));
+  image.set(header);
+  const data = new DataView(image.buffer, header.length);
+  pixels.forEach((pixel, i) => {
+    const value = Math.min(pixel, max_value);
+    if (wide) data.setUint16(2 * i, value); // big-endian
+    else data.setUint8(i, value);
+  });
+  return image;
+}
+
+function to_base64(bytes) {
+  let binary = "";
+  for (let i = 0; i < bytes.length; i += 0x8000)
+    binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+  return btoa(binary);
+}
+
+function mandelbrot_with_alloc(kernel, width, height, max_iter, center_x, center_y, zoom) {
   const pointer = alloc(width * height);
 
   const start = performance.now();
-  mandelbrot_in_memory(pointer, width, height, max_iter);
+  kernel(pointer, width, height, max_iter, center_x, center_y, zoom);
   const end = performance.now();
   const wasmArray = new Uint32Array(memory.buffer, pointer, width * height);
   const resultArray = Array.from(wasmArray);
@@ -169,6 +271,81 @@ function mandelbrot_with_alloc(width, height, max_iter) {
   return [end - start, returnArray];
 }
 
+// Runs a kernel that reads one wasm buffer and writes another of the same
+// length, such as the image filters and the Game of Life steps
+function buffer_kernel_with_alloc(kernel, ArrayType, alloc_fn, dealloc_fn, input, ...args) {
+  const pointer_in = alloc_fn(input.length);
+  const pointer_out = alloc_fn(input.length);
+  new ArrayType(memory.buffer, pointer_in, input.length).set(input);
+
+  const start = performance.now();
+  kernel(pointer_in, pointer_out, ...args);
+  const end = performance.now();
+  const resultArray = Array.from(new ArrayType(memory.buffer, pointer_out, input.length));
+  dealloc_fn(pointer_in, input.length);
+  dealloc_fn(pointer_out, input.length);
+
+  // Print random 10 elements of result array to prevent optimization
+  const returnArray = [];
+  for (let i = 0; i < 10; i++) {
+    const index = Math.floor(Math.random() * resultArray.length);
+    returnArray.push(resultArray[index]);
+  }
+  return [end - start, returnArray];
+}
+
+function image_filter_u8_with_alloc(image, width, height, filter) {
+  return buffer_kernel_with_alloc(image_filter_u8_in_memory, Uint8Array, allocu8, deallocu8,
+    image, width, height, filter);
+}
+
+function image_filter_f32_with_alloc(image, width, height, filter) {
+  return buffer_kernel_with_alloc(image_filter_f32_in_memory, Float32Array, allocf32, deallocf32,
+    image, width, height, filter);
+}
+
+function life_with_alloc(board, width, height, steps) {
+  return buffer_kernel_with_alloc(life_in_memory, Uint8Array, allocu8, deallocu8,
+    board, width, height, steps);
+}
+
+function raytrace_with_alloc(width, height, samples) {
+  const len = width * height * 3;
+  const pointer = allocu8(len);
+
+  const start = performance.now();
+  raytrace_in_memory(pointer, width, height, samples);
+  const end = performance.now();
+  const resultArray = Array.from(new Uint8Array(memory.buffer, pointer, len));
+  deallocu8(pointer, len);
+
+  // Print random 10 elements of result array to prevent optimization
+  const returnArray = [];
+  for (let i = 0; i < 10; i++) {
+    const index = Math.floor(Math.random() * resultArray.length);
+    returnArray.push(resultArray[index]);
+  }
+  return [end - start, returnArray];
+}
+
+function bignum_limbs(value) {
+  const limbs = value.limbs();
+  value.free();
+  return limbs;
+}
+
+const image_filters = {
+  "box": ImageFilter.Box,
+  "gaussian": ImageFilter.Gaussian,
+  "sobel": ImageFilter.Sobel,
+};
+
+// Native counterpart of the json_*_roundtrip kernels, to compare against the
+// browser's own JSON implementation
+function json_native_roundtrip(document) {
+  return JSON.stringify(JSON.parse(document)).length;
+}
+
 console.warn("Started...");
 const testlist = await (await fetch('testlist')).json();
 if (!testlist) {
@@ -188,23 +365,98 @@ console.warn(`Loaded ${tests.length} tests.`);
 const functions = {
   "fibonacci": fibonacci,
   "eratostenes": eratostenes,
+  "eratostenes_bitpacked": eratostenes_bitpacked,
+  "eratostenes_odds": eratostenes_odds,
+  "eratostenes_segmented": eratostenes_segmented,
+  "eratostenes_primes": (limit) => eratostenes_primes(limit).length,
   "quicksort": qsort_with_alloc,
   "montecarlo": montecarlo,
+  "montecarlo_prng": montecarlo_prng,
   "nqueens": nqueens,
   "matrix_multiply": matrix_multiply_with_alloc,
-  "mandelbrot": mandelbrot_with_alloc,
+  "mandelbrot": (...args) => mandelbrot_with_alloc(mandelbrot_view_in_memory, ...args),
+  "mandelbrot_f32": (...args) => mandelbrot_with_alloc(mandelbrot_view_f32_in_memory, ...args),
+  "text_utf8_validate": text_utf8_validate_in_memory,
+  "text_chars": text_chars_in_memory,
+  "text_to_lowercase": text_to_lowercase_in_memory,
+  "text_split_words": text_split_words_in_memory,
+  "text_search": text_search_in_memory,
+  "text_word_frequency": text_word_frequency_in_memory,
+  "json_value_roundtrip": json_value_roundtrip_in_memory,
+  "json_typed_roundtrip": json_typed_roundtrip_in_memory,
+  "json_native_roundtrip": json_native_roundtrip,
+  "levenshtein_full": levenshtein_full_in_memory,
+  "levenshtein_two_rows": levenshtein_two_rows_in_memory,
+  "lcs_full": lcs_full_in_memory,
+  "lcs_two_rows": lcs_two_rows_in_memory,
+  "graph_bfs": graph_bfs,
+  "graph_dijkstra": graph_dijkstra,
+  "image_filter_u8": image_filter_u8_with_alloc,
+  "image_filter_f32": image_filter_f32_with_alloc,
+  "life_steps": life_with_alloc,
+  "raytrace": raytrace_with_alloc,
+  // Only the limb arithmetic, as in the native harness, keeping the number of
+  // limbs for the accumulator
+  "bignum_factorial": (n) => bignum_limbs(bignum_factorial_value(n)),
+  "bignum_power": (base, exponent) => bignum_limbs(bignum_power_value(BigInt(base), exponent)),
+  "bignum_pi": (digits) => bignum_limbs(bignum_pi_value(digits)),
+  // The u64 variants return a BigInt, which can't be added to the accumulator
+  "fibonacci_recursive": (n) => Number(fibonacci_recursive(n)),
+  "fibonacci_iterative": (n) => Number(fibonacci_iterative(n)),
+  "fibonacci_memoized": (n) => Number(fibonacci_memoized(n)),
+  "fibonacci_matrix": (n) => Number(fibonacci_matrix(n)),
+  "fibonacci_tail": (n) => Number(fibonacci_tail(n)),
+  "abstraction_iterator": abstraction_iterator_in_memory,
+  "abstraction_index": abstraction_index_in_memory,
+  "abstraction_generic": abstraction_generic_in_memory,
+  "abstraction_dyn": abstraction_dyn_in_memory,
+  "abstraction_closure": abstraction_closure_in_memory,
+  "abstraction_fn_pointer": abstraction_fn_pointer_in_memory,
+  "intarith_div_i32": intarith_div_i32_in_memory,
+  "intarith_div_i64": intarith_div_i64_in_memory,
+  "intarith_checked": intarith_checked_in_memory,
+  "intarith_count_primes": intarith_count_primes_in_memory,
+  // The sync kernels return a BigInt, or undefined when threads can't be
+  // spawned, which is always the case for more than one thread here
+  "sync_atomic_fetch_add": (threads, ops) => Number(sync_atomic_fetch_add(threads, ops)),
+  "sync_atomic_cas": (threads, ops) => Number(sync_atomic_cas(threads, ops)),
+  "sync_mutex": (threads, ops) => Number(sync_mutex(threads, ops)),
+  "sync_rwlock": (threads, ops, write_every) => Number(sync_rwlock(threads, ops, write_every)),
+  "memory_push": memory_push,
+  "memory_push_with_capacity": memory_push_with_capacity,
+  "memory_extend": memory_extend,
+  "memory_mixed_alloc": memory_mixed_alloc,
+  "nqueens_bitmask": nqueens_bitmask,
+  "nqueens_fundamental": nqueens_fundamental,
+  "nqueens_solutions": (n) => {
+    const solutions = nqueens_solutions(n);
+    const count = solutions.count();
+    solutions.free();
+    return count;
+  },
 }
 
+const montecarlo_prngs = {
+  "chacha": Prng.ChaCha,
+  "xorshift": Prng.Xorshift,
+  "pcg": Prng.Pcg,
+  "splitmix": Prng.SplitMix,
+};
+
 for (let i = 0; i < tests.length; i++) {
   const test = tests[i];
   console.warn(`Running test: ${test.fun}, idx: ${i}`);
   const func = functions[test.fun];
   const {arg, name, executions} = test;
+  if (!func) {
+    console.warn(`Skipping test ${name}, unknown function ${test.fun}`);
+    continue;
+  }
 
   let benchmarking_func;
-  if (test.type === 1 || test.type === 3)
+  if (test.type === 1 || test.type === 3 || test.type === 6 || test.type === 7 || test.type === 8 || test.type === 9 || test.type === 12 || test.type === 13 || test.type === 14 || test.type === 15 || test.type === 16 || test.type === 17 || test.type === 18 || test.type === 19)
     benchmarking_func = benchmark;
-  else if (test.type === 2 || test.type === 4 || test.type === 5)
+  else if (test.type === 2 || test.type === 4 || test.type === 5 || test.type === 10 || test.type === 11)
     benchmarking_func = benchmark_2;
 
   if (test.type === 4) {
@@ -213,10 +465,116 @@ for (let i = 0; i < tests.length; i++) {
     benchmarking_func(executions, name, func, allocated_args, width);
     dealloc_matrix_multiply_args(allocated_args);
     
+  } else if (test.type === 1 && test.fun.startsWith("eratostenes")) {
+    // The sieve variants differ mostly in the linear memory they need
+    const pages_before = memory_pages();
+    benchmarking_func(executions, name, func, arg);
+    console.log(JSON.stringify({ fun: name, report: "memory",
+      values: { growing_calls: null, pages_before, pages_after: memory_pages(), first_call_ns: null, growth_ns: null } }));
+  } else if (test.type === 6) {
+    const [pointer, len] = text_to_wasm_memory(text_corpus(arg.len, arg.non_ascii_ratio, arg.seed));
+    if (test.fun === "text_search")
+      benchmarking_func(executions, name, func, pointer, len, arg.needle);
+    else
+      benchmarking_func(executions, name, func, pointer, len);
+    deallocu8(pointer, len);
+  } else if (test.type === 7 && test.fun === "json_native_roundtrip") {
+    const document = json_document(arg.records, arg.shape, arg.seed);
+    benchmarking_func(executions, name, func, document);
+  } else if (test.type === 7) {
+    const [pointer, len] = text_to_wasm_memory(json_document(arg.records, arg.shape, arg.seed));
+    benchmarking_func(executions, name, func, pointer, len);
+    deallocu8(pointer, len);
+  } else if (test.type === 8) {
+    const a = dp_sequence(arg.len_a, arg.alphabet, arg.seed);
+    const b = dp_sequence(arg.len_b, arg.alphabet, arg.seed + 1);
+    const pointer_a = to_wasm_memory(Uint8Array, allocu8, a);
+    const pointer_b = to_wasm_memory(Uint8Array, allocu8, b);
+    benchmarking_func(executions, name, func, pointer_a, a.length, pointer_b, b.length);
+    deallocu8(pointer_a, a.length);
+    deallocu8(pointer_b, b.length);
+  } else if (test.type === 9) {
+    if (arg.kind !== "grid" && arg.vertices === 0) {
+      console.warn(`Skipping test ${name}, a random graph needs vertices`);
+      continue;
+    }
+    const graph = arg.kind === "grid"
+      ? CsrGraph.grid(arg.width, arg.height, arg.seed)
+      : CsrGraph.random(arg.vertices, arg.edges, arg.seed);
+    if (arg.source >= graph.vertices()) {
+      console.warn(`Skipping test ${name}, source ${arg.source} is not in a graph of ${graph.vertices()} vertices`);
+      graph.free();
+      continue;
+    }
+    benchmarking_func(executions, name, func, graph, arg.source);
+    graph.free();
+  } else if (test.type === 10 && test.fun === "life_steps") {
+    const board = life_generate(arg.width, arg.height, arg.seed);
+    benchmarking_func(executions, name, func, board, arg.width, arg.height, arg.steps);
+  } else if (test.type === 10) {
+    const image = image_generate(arg.width, arg.height, arg.seed);
+    const input = test.fun === "image_filter_f32" ? image_to_f32(image) : image;
+    benchmarking_func(executions, name, func, input, arg.width, arg.height, image_filters[arg.filter]);
   } else if (test.type === 5) {
-    const {width, height, max_iter} = arg;
-    benchmarking_func(executions, name, func, width, height, max_iter);
-  }else {
+    // Without a viewport the whole set is rendered, as `mandelbrot` does
+    const view = [arg.width, arg.height, arg.max_iter, arg.center_x ?? -0.75, arg.center_y ?? 0, arg.zoom ?? 1];
+    benchmarking_func(executions, name, func, ...view);
+    if (dump_images) {
+      const kernel = test.fun === "mandelbrot_f32" ? mandelbrot_view_f32_in_memory : mandelbrot_view_in_memory;
+      const pgm = pgm_image(arg.width, arg.height, arg.max_iter, mandelbrot_pixels(kernel, ...view));
+      console.log(JSON.stringify({ fun: name, report: "image", values: { pgm: to_base64(pgm) } }));
+    }
+  } else if (test.type === 11) {
+    benchmarking_func(executions, name, func, arg.width, arg.height, arg.samples);
+  } else if (test.type === 12) {
+    if (test.fun === "bignum_factorial")
+      benchmarking_func(executions, name, func, arg.n);
+    else if (test.fun === "bignum_power")
+      benchmarking_func(executions, name, func, arg.base, arg.exponent);
+    else
+      benchmarking_func(executions, name, func, arg.digits);
+  } else if (test.type === 15) {
+    const prng = montecarlo_prngs[arg.prng];
+    // Only the estimate goes into the accumulator; the same estimate and its
+    // standard error are reported once afterwards
+    benchmarking_func(executions, name, (points, prng, seed) => {
+      const result = func(points, prng, seed);
+      const estimate = result.estimate;
+      result.free();
+      return estimate;
+    }, arg.points, prng, arg.seed);
+    const result = func(arg.points, prng, arg.seed);
+    console.log(JSON.stringify({ fun: name, report: "estimate",
+      values: { estimate: result.estimate, std_error: result.std_error } }));
+    result.free();
+  } else if (test.type === 16 || test.type === 17) {
+    const data = test.type === 16 ? abstraction_data(arg.len, arg.seed) : intarith_data(arg.len, arg.seed);
+    const pointer = to_wasm_memory(Uint32Array, allocu32, data);
+    benchmarking_func(executions, name, func, pointer, data.length);
+    deallocu32(pointer, data.length);
+  } else if (test.type === 18) {
+    if (Number.isNaN(func(arg.threads, 1, arg.write_every))) {
+      console.warn(`Skipping test ${name}, ${arg.threads} threads can't be spawned`);
+      continue;
+    }
+    benchmarking_func(executions, name, func, arg.threads, arg.ops, arg.write_every);
+  } else if (test.type === 19) {
+    const growing_calls_before = memory_growing_calls();
+    const pages_before = memory_pages();
+    const args = test.fun === "memory_extend" ? [arg.count, arg.chunk]
+      : test.fun === "memory_mixed_alloc" ? [arg.rounds, arg.seed]
+      : [arg.count];
+    // The first call is usually the only one that grows linear memory, and the
+    // warmup keeps it out of the times, so it is timed on its own. Growing
+    // took about as long as it took more than the steady-state calls
+    const start = performance.now();
+    func(...args);
+    const first_call_ns = (performance.now() - start) * 1e6;
+    const times = benchmarking_func(executions, name, func, ...args);
+    const growth_ns = first_call_ns - median(times) * 1e6;
+    console.log(JSON.stringify({ fun: name, report: "memory",
+      values: { growing_calls: memory_growing_calls() - growing_calls_before, pages_before, pages_after: memory_pages(), first_call_ns, growth_ns } }));
+  } else {
     benchmarking_func(executions, name, func, arg);
   }
 